@@ -0,0 +1,211 @@
+// Copyright 2013 Luis de Bethencourt <luis@debethencourt.com>
+// Copyright 2013 The Rust Project Developers
+// http://rust-lang.org
+
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * bahai module for the datetime library of the Rust programming language
+ *
+ * The Bahá'í (Badí') calendar has 19 months of 19 days. The 4 or 5
+ * intercalary days of Ayyám-i-Há go between the 18th and the 19th month.
+ * Years start at Naw-Rúz, taken here as the fixed 21st of March, and are
+ * counted from Naw-Rúz 1844 (1 BE). Since 2015 (172 BE) Naw-Rúz follows the
+ * vernal equinox and may fall on the 20th; that is not accounted for.
+ */
+
+use gcalendar::{GCalendar, DateError, InvalidYear, InvalidMonth, InvalidDay};
+use gcalendar::{day_of_year, year_size};
+
+static BAHAIEPOCHYEAR: uint = 1844;
+static MONTHDAYS: uint = 19;
+
+/**
+ * Month names, indexed by month number. Month 0 stands for Ayyám-i-Há.
+ */
+pub static BAHAI_MONTH_NAMES: [&'static str, ..20] = [
+    "Ayyám-i-Há", "Bahá", "Jalál", "Jamál", "'Azamat", "Núr", "Rahmat",
+    "Kalimát", "Kamál", "Asmá'", "'Izzat", "Mashíyyat", "'Ilm", "Qudrat",
+    "Qawl", "Masá'il", "Sharaf", "Sultán", "Mulk", "'Alá'"];
+
+#[deriving(Eq, Clone)]
+pub struct BahaiDate {
+    /*
+     * Date in the Bahá'í calendar.
+     */
+    year: uint,        /* Year BE, 1 from Naw-Rúz 1844       */
+    month: uint,       /* Month [1-19], 0 for Ayyám-i-Há    */
+    day: uint,         /* Day   [1-19], [1-5] in Ayyám-i-Há */
+}
+
+/*
+ * Number of Ayyám-i-Há days in the Bahá'í year starting at Naw-Rúz of the
+ * given Gregorian year. The year ends in February of the next one.
+ */
+fn ayyam_i_ha_days(naw_ruz_year: uint) -> uint {
+    year_size(naw_ruz_year + 1) - 19 * MONTHDAYS
+}
+
+impl BahaiDate {
+    /**
+    * Allocates a BahaiDate object, checking that the day exists.
+    */
+    pub fn new(year: uint, month: uint, day: uint) -> Result<BahaiDate, DateError> {
+        if year < 1 {
+            return Err(InvalidYear);
+        }
+        if month > 19 {
+            return Err(InvalidMonth);
+        }
+        let days = if month == 0 {
+            ayyam_i_ha_days(year + BAHAIEPOCHYEAR - 1)
+        } else {
+            MONTHDAYS
+        };
+        if day < 1 || day > days {
+            return Err(InvalidDay);
+        }
+
+        Ok(BahaiDate { year: year, month: month, day: day })
+    }
+
+    /**
+    * Converts a proleptic Gregorian date, which must be valid and not before
+    * Naw-Rúz 1844. Fails otherwise.
+    */
+    pub fn from_gregorian(year: uint, month: uint, mday: uint) -> BahaiDate {
+        let yday = day_of_year(year, month, mday).unwrap();
+        let naw_ruz = day_of_year(year, 3, 21).unwrap();
+        assert!(year > BAHAIEPOCHYEAR || (year == BAHAIEPOCHYEAR && yday >= naw_ruz));
+
+        let (naw_ruz_year, n) = if yday >= naw_ruz {
+            (year, yday - naw_ruz)
+        } else {
+            (year - 1, year_size(year - 1) - day_of_year(year - 1, 3, 21).unwrap() + yday)
+        };
+
+        let ayyam_i_ha = ayyam_i_ha_days(naw_ruz_year);
+        let (month, day) = if n < 18 * MONTHDAYS {
+            (n / MONTHDAYS + 1, n % MONTHDAYS + 1)
+        } else if n < 18 * MONTHDAYS + ayyam_i_ha {
+            (0, n - 18 * MONTHDAYS + 1)
+        } else {
+            (19, n - 18 * MONTHDAYS - ayyam_i_ha + 1)
+        };
+
+        BahaiDate {
+            year: naw_ruz_year - BAHAIEPOCHYEAR + 1,
+            month: month,
+            day: day
+        }
+    }
+
+    /**
+    * Returns the proleptic Gregorian (year, month, day) of this date.
+    */
+    pub fn to_gregorian(&self) -> (uint, uint, uint) {
+        let naw_ruz_year = self.year + BAHAIEPOCHYEAR - 1;
+        let n = match self.month {
+            0 => 18 * MONTHDAYS + self.day - 1,
+            19 => 18 * MONTHDAYS + ayyam_i_ha_days(naw_ruz_year) + self.day - 1,
+            m => (m - 1) * MONTHDAYS + self.day - 1
+        };
+
+        let mut year = naw_ruz_year;
+        let mut yday = day_of_year(year, 3, 21).unwrap() + n;
+        if yday >= year_size(year) {
+            yday -= year_size(year);
+            year += 1;
+        }
+
+        let cal = GCalendar::from_ordinal_date(year, yday + 1).unwrap();
+        (cal.year, cal.month, cal.mday)
+    }
+
+    pub fn get_year(&self) -> uint {
+        self.year
+    }
+
+    pub fn get_month(&self) -> uint {
+        self.month
+    }
+
+    pub fn get_day(&self) -> uint {
+        self.day
+    }
+
+    /**
+    * Returns the name of the month, "Ayyám-i-Há" for the intercalary days.
+    */
+    pub fn month_name(&self) -> &'static str {
+        BAHAI_MONTH_NAMES[self.month]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BahaiDate;
+    use gcalendar::GCalendar;
+    use gcalendar::{InvalidYear, InvalidMonth, InvalidDay};
+
+    #[test]
+    fn from_gregorian() {
+        let bd = BahaiDate::from_gregorian(1844, 3, 21);
+        assert_eq!((bd.year, bd.month, bd.day), (1, 1, 1));
+
+        let bd = BahaiDate::from_gregorian(2024, 3, 20);
+        assert_eq!((bd.year, bd.month, bd.day), (180, 19, 19));
+        let bd = BahaiDate::from_gregorian(2024, 3, 21);
+        assert_eq!((bd.year, bd.month, bd.day), (181, 1, 1));
+        assert_eq!(bd.month_name(), "Bahá");
+
+        /* 181 BE ends in 2025, not a leap year */
+        let bd = BahaiDate::from_gregorian(2025, 2, 25);
+        assert_eq!((bd.year, bd.month, bd.day), (181, 18, 19));
+        let bd = BahaiDate::from_gregorian(2025, 2, 26);
+        assert_eq!((bd.year, bd.month, bd.day), (181, 0, 1));
+        assert_eq!(bd.month_name(), "Ayyám-i-Há");
+        let bd = BahaiDate::from_gregorian(2025, 3, 1);
+        assert_eq!((bd.year, bd.month, bd.day), (181, 0, 4));
+        let bd = BahaiDate::from_gregorian(2025, 3, 2);
+        assert_eq!((bd.year, bd.month, bd.day), (181, 19, 1));
+
+        /* 180 BE ends in 2024, a leap year */
+        let bd = BahaiDate::from_gregorian(2024, 3, 1);
+        assert_eq!((bd.year, bd.month, bd.day), (180, 0, 5));
+    }
+
+    #[test]
+    fn to_gregorian() {
+        assert_eq!(BahaiDate::new(181, 1, 1).unwrap().to_gregorian(), (2024, 3, 21));
+        assert_eq!(BahaiDate::new(181, 0, 4).unwrap().to_gregorian(), (2025, 3, 1));
+        assert_eq!(BahaiDate::new(180, 0, 5).unwrap().to_gregorian(), (2024, 3, 1));
+        assert_eq!(BahaiDate::new(181, 19, 19).unwrap().to_gregorian(), (2025, 3, 20));
+
+        /* Every day of 2023 and 2024 */
+        for i in range(0i64, 731) {
+            let gc = GCalendar::from_epoch_millis(1672531200000 + i * 86400000);
+            let bd = BahaiDate::from_gregorian(gc.year, gc.month, gc.mday);
+            assert_eq!(bd.to_gregorian(), (gc.year, gc.month, gc.mday));
+        }
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(BahaiDate::new(0, 1, 1).unwrap_err(), InvalidYear);
+        assert_eq!(BahaiDate::new(181, 20, 1).unwrap_err(), InvalidMonth);
+        assert_eq!(BahaiDate::new(181, 1, 20).unwrap_err(), InvalidDay);
+        assert_eq!(BahaiDate::new(181, 0, 5).unwrap_err(), InvalidDay);
+        assert!(BahaiDate::new(180, 0, 5).is_ok());
+    }
+
+    #[test]
+    #[should_fail]
+    fn from_gregorian_before_epoch() {
+        BahaiDate::from_gregorian(1844, 3, 20);
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright 2013 Luis de Bethencourt <luis@debethencourt.com>
+// Copyright 2013 The Rust Project Developers
+// http://rust-lang.org
+
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * chinese module for the datetime library of the Rust programming language
+ *
+ * Years of the Chinese sexagenary cycle, named by one of ten heavenly stems
+ * and one of twelve earthly branches. The cycle started again with Jiazi
+ * in 1984. Years are matched to Gregorian years as a whole; the Chinese new
+ * year, which falls between late January and February, is not computed.
+ */
+
+#[deriving(Eq, Clone)]
+pub enum HeavenlyStem {
+    Jia,
+    Yi,
+    Bing,
+    Ding,
+    Mou,    /* 戊, usually "Wu" in pinyin, which is taken by the branch */
+    Ji,
+    Geng,
+    Xin,
+    Ren,
+    Gui,
+}
+
+#[deriving(Eq, Clone)]
+pub enum EarthlyBranch {
+    Zi,
+    Chou,
+    Yin,
+    Mao,
+    Chen,
+    Si,
+    Wu,
+    Wei,
+    Shen,
+    You,
+    Xu,
+    Hai,
+}
+
+static STEMS: [HeavenlyStem, ..10] = [Jia, Yi, Bing, Ding, Mou, Ji, Geng, Xin, Ren, Gui];
+static BRANCHES: [EarthlyBranch, ..12] = [Zi, Chou, Yin, Mao, Chen, Si, Wu, Wei, Shen,
+                                          You, Xu, Hai];
+
+/* A year of the Gregorian calendar starting a cycle */
+static CYCLESTART: uint = 1984;
+
+pub static ZODIAC_ANIMALS: [&'static str, ..12] = [
+    "Rat", "Ox", "Tiger", "Rabbit", "Dragon", "Snake", "Horse", "Goat",
+    "Monkey", "Rooster", "Dog", "Pig"];
+
+/*
+ * Position [0-59] of the year in the sexagenary cycle.
+ */
+fn cycle_position(year: uint) -> uint {
+    (year as int - CYCLESTART as int).mod_floor(&60) as uint
+}
+
+/**
+ * Returns the stem and branch naming the given year.
+ */
+pub fn stem_branch_of_year(year: uint) -> (HeavenlyStem, EarthlyBranch) {
+    let pos = cycle_position(year);
+    (STEMS[pos % 10], BRANCHES[pos % 12])
+}
+
+/**
+ * Returns the zodiac animal of the given year, the one of its earthly branch.
+ */
+pub fn zodiac_animal_year(year: uint) -> &'static str {
+    ZODIAC_ANIMALS[cycle_position(year) % 12]
+}
+
+/**
+ * Returns the years in [from, to] named by the given stem and branch. Stems
+ * and branches of different parity never meet, and give no years.
+ */
+pub fn years_with_stem_branch(stem: HeavenlyStem, branch: EarthlyBranch,
+                              from: uint, to: uint) -> ~[uint] {
+    let mut years = ~[];
+    let mut year = from;
+    while year <= to {
+        let (s, b) = stem_branch_of_year(year);
+        if s == stem && b == branch {
+            years.push(year);
+            year += 60;
+        } else {
+            year += 1;
+        }
+    }
+    years
+}
+
+#[cfg(test)]
+mod test {
+    use super::{stem_branch_of_year, zodiac_animal_year, years_with_stem_branch};
+    use super::{Jia, Yi, Mou, Gui, Zi, Chou, Xu, Hai};
+
+    #[test]
+    fn test_stem_branch_of_year() {
+        assert_eq!(stem_branch_of_year(1984), (Jia, Zi));
+        assert_eq!(stem_branch_of_year(1985), (Yi, Chou));
+        assert_eq!(stem_branch_of_year(2043), (Gui, Hai));
+        assert_eq!(stem_branch_of_year(2044), (Jia, Zi));
+        assert_eq!(stem_branch_of_year(1924), (Jia, Zi));
+        assert_eq!(stem_branch_of_year(2018), (Mou, Xu));
+    }
+
+    #[test]
+    fn test_zodiac_animal_year() {
+        assert_eq!(zodiac_animal_year(1984), "Rat");
+        assert_eq!(zodiac_animal_year(2009), "Ox");
+        assert_eq!(zodiac_animal_year(2024), "Dragon");
+        assert_eq!(zodiac_animal_year(1900), "Rat");
+    }
+
+    #[test]
+    fn test_years_with_stem_branch() {
+        assert_eq!(years_with_stem_branch(Jia, Zi, 1900, 2100), ~[1924, 1984, 2044]);
+        assert_eq!(years_with_stem_branch(Jia, Zi, 1984, 1984), ~[1984]);
+        assert_eq!(years_with_stem_branch(Jia, Chou, 1900, 2100), ~[]);
+    }
+}
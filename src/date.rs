@@ -13,10 +13,44 @@
  *
  */
 
-use std::io;
+use std::to_bytes;
+use std::num::{CheckedAdd, CheckedMul};
 use extra::time;
-use gcalendar::GCalendar;
+use extra::time::Timespec;
+use extra::serialize::{Encodable, Decodable, Encoder, Decoder};
+use gcalendar::{GCalendar, DateError, day_of_year, day_of_week, is_leap_year, year_size};
+use gcalendar::{InvalidYear, InvalidMonth, InvalidDay, InvalidHour};
+use gcalendar::{InvalidMinute, InvalidSecond, InvalidMillisecond, InvalidTimezone};
+use gcalendar::InvalidNanosecond;
+use period::Period;
+use bahai::BahaiDate;
+use chinese;
+use chinese::{HeavenlyStem, EarthlyBranch};
+use format::{CompiledFormat, FormatError, Literal, Specifier};
+use iana_tz::iana_standard_offset;
+use types::Weekday;
+use timezone::TimeZone;
+use range::DateRange;
+use duration::Duration;
+use parse;
+use parse::{ParseError, Parsed};
 
+/* Milliseconds since epoch of 0000-01-01 00:00:00, the earliest Date */
+static YEARZEROMILLIS: i64 = -62167219200000;
+static EPOCHJULIANDAY: i64 = 2440588;
+
+/**
+ * Unit of a number counted from the Unix epoch.
+ */
+#[deriving(Eq)]
+pub enum EpochUnit {
+    Seconds,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+#[deriving(Eq, Clone)]
 pub struct Date {
     /**
     * Gregorian Calendar
@@ -24,176 +58,3576 @@ pub struct Date {
     priv gcal: GCalendar,
     /**
     * Number of milliseconds since the standard base time known as "epoch",
-    * namely 1st of January, 1970, 00:00:00 GMT.
+    * namely 1st of January, 1970, 00:00:00 GMT, negative for dates before
+    * it.
     */
-    priv since_epoch: uint,
+    priv since_epoch: i64,
+}
+
+/**
+ * A Date together with the offset of the timezone it is seen from.
+ */
+pub struct LocalDate {
+    priv date: Date,          /* The instant, as in UTC         */
+    priv offset: int,         /* Minutes east of UTC            */
+    priv zone: ~str,          /* Name of the timezone           */
+    priv local: GCalendar,    /* Calendar fields in local time  */
 }
 
 impl Date {
     /**
     * Allocates a Date object and initializes it to represent the specified
-    * number of milliseconds since epoch.
+    * number of milliseconds since epoch, negative for dates before it.
+    * Dates before year 0, -62167219200000, are not supported and fail, see
+    * from_timestamp_millis_checked.
     */
-    pub fn from_epoch(epoch_date: uint) -> Date {
-        let cal: GCalendar = GCalendar::new_from_epoch(epoch_date);
+    pub fn from_timestamp_millis(ms: i64) -> Date {
+        let cal: GCalendar = GCalendar::from_epoch_millis(ms);
         Date {
             gcal: cal,
-            since_epoch: epoch_date
+            since_epoch: ms
+        }
+    }
+
+    /**
+     * Like from_timestamp_millis, but returns None for dates before year 0
+     * instead of failing.
+     */
+    pub fn from_timestamp_millis_checked(ms: i64) -> Option<Date> {
+        if ms < YEARZEROMILLIS { None } else { Some(Date::from_timestamp_millis(ms)) }
+    }
+
+    /**
+     * Allocates a Date object from its proleptic Gregorian calendar fields,
+     * checking that they make a valid date and time. Leap seconds
+     * (sec == 60) are accepted. This is the constructor to use when
+     * starting from calendar fields.
+     */
+    pub fn from_proleptic_gregorian(year: uint, month: uint, day: uint,
+                                    hour: uint, min: uint, sec: uint,
+                                    ms: uint) -> Result<Date, DateError> {
+        if month < 1 || month > 12 {
+            return Err(InvalidMonth);
+        }
+        if day_of_year(year, month, day).is_none() {
+            return Err(InvalidDay);
+        }
+        if hour > 23 {
+            return Err(InvalidHour);
+        }
+        if min > 59 {
+            return Err(InvalidMinute);
+        }
+        if sec > 60 {
+            return Err(InvalidSecond);
+        }
+        if ms > 999 {
+            return Err(InvalidMillisecond);
+        }
+
+        Ok(Date::from_calendar(year, month, day, hour, min, sec, ms))
+    }
+
+    /*
+     * Allocates a Date object from calendar fields already known to be valid.
+     */
+    fn from_calendar(year: uint, month: uint, day: uint, hour: uint,
+                     min: uint, sec: uint, ms: uint) -> Date {
+        let yday = day_of_year(year, month, day).unwrap();
+        let cal = GCalendar::new(sec, min, hour, day, month, year, 0, yday);
+        Date::from_timestamp_millis(cal.mktime() * 1000 + ms as i64)
+    }
+
+    /**
+     * Allocates a Date object at midnight of the given day, checking that it
+     * exists. Negative years give InvalidYear.
+     */
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Result<Date, DateError> {
+        Date::from_ymd_hms(year, month, day, 0, 0, 0)
+    }
+
+    /**
+     * Allocates a Date object at the given day and time, checking that they
+     * exist. Negative years give InvalidYear.
+     */
+    pub fn from_ymd_hms(year: i32, month: u32, day: u32, hour: u32, min: u32,
+                        sec: u32) -> Result<Date, DateError> {
+        if year < 0 {
+            return Err(InvalidYear);
+        }
+        Date::from_proleptic_gregorian(year as uint, month as uint, day as uint,
+                                       hour as uint, min as uint, sec as uint, 0)
+    }
+
+    /**
+     * Allocates a Date object at the given day and time in UTC, checking
+     * that they exist.
+     */
+    pub fn from_parts_utc(year: u32, month: u32, day: u32, hour: u32, min: u32,
+                          sec: u32) -> Result<Date, DateError> {
+        Date::from_proleptic_gregorian(year as uint, month as uint, day as uint,
+                                       hour as uint, min as uint, sec as uint, 0)
+    }
+
+    /**
+     * Allocates a Date object at the given day and time, read as the local
+     * time of the timezone and stored in UTC. A local time that is in UTC
+     * before year 0 gives InvalidYear.
+     */
+    pub fn from_parts_local<Tz: TimeZone>(year: u32, month: u32, day: u32, hour: u32,
+                                          min: u32, sec: u32, tz: &Tz)
+                                          -> Result<Date, DateError> {
+        match Date::from_parts_utc(year, month, day, hour, min, sec) {
+            Ok(d) => {
+                let utc = d.since_epoch - tz.offset_seconds() as i64 * 1000;
+                if utc < YEARZEROMILLIS {
+                    return Err(InvalidYear);
+                }
+                Ok(Date::from_timestamp_millis(utc))
+            }
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+     * Allocates a Date object at midnight of the given day of the year
+     * [1-366], as in the ISO 8601 ordinal date "2009-044". A day not in the
+     * year gives InvalidDay and a negative year InvalidYear.
+     */
+    pub fn from_ordinal(year: i32, day_of_year: u32) -> Result<Date, DateError> {
+        if year < 0 {
+            return Err(InvalidYear);
+        }
+        match GCalendar::from_ordinal_date(year as uint, day_of_year as uint) {
+            Ok(cal) => Ok(Date::from_calendar(cal.year, cal.month, cal.mday, 0, 0, 0, 0)),
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+     * Allocates a Date object at the given time of the current day, in UTC.
+     */
+    pub fn from_hms(hour: u32, min: u32, sec: u32) -> Result<Date, DateError> {
+        Date::from_hms_on(hour, min, sec, &Date::now().gcal)
+    }
+
+    fn from_hms_on(hour: u32, min: u32, sec: u32, today: &GCalendar) -> Result<Date, DateError> {
+        Date::from_proleptic_gregorian(today.year, today.month, today.mday,
+                                       hour as uint, min as uint, sec as uint, 0)
+    }
+
+    /**
+     * Like from_proleptic_gregorian, but missing fields are taken from the
+     * current day (year, month and day) or are 0 (hour, min, sec and ms).
+     *
+     * from_parts_opt(None, Some(12), Some(25), None, None, None, None) is
+     * Christmas of the current year at midnight.
+     */
+    pub fn from_parts_opt(year: Option<uint>, month: Option<uint>, day: Option<uint>,
+                          hour: Option<uint>, min: Option<uint>, sec: Option<uint>,
+                          ms: Option<uint>) -> Result<Date, DateError> {
+        Date::from_parts_opt_on(year, month, day, hour, min, sec, ms, &Date::now().gcal)
+    }
+
+    fn from_parts_opt_on(year: Option<uint>, month: Option<uint>, day: Option<uint>,
+                         hour: Option<uint>, min: Option<uint>, sec: Option<uint>,
+                         ms: Option<uint>, today: &GCalendar) -> Result<Date, DateError> {
+        Date::from_proleptic_gregorian(year.unwrap_or(today.year),
+                                       month.unwrap_or(today.month),
+                                       day.unwrap_or(today.mday),
+                                       hour.unwrap_or(0), min.unwrap_or(0),
+                                       sec.unwrap_or(0), ms.unwrap_or(0))
+    }
+
+    /**
+     * Returns the date shown at the given cell of a month view with 6 rows
+     * [0-5] of 7 columns [0-6], the first column being first_day, or None
+     * if the cell is outside the month.
+     */
+    pub fn from_calendar_position(year: uint, month: uint, row: uint, col: uint,
+                                  first_day: Weekday) -> Option<Date> {
+        if month < 1 || month > 12 || row > 5 || col > 6 {
+            return None;
+        }
+
+        let first_wday = first_day.number_from_sunday() as uint;
+        let first_col = (day_of_week(year, month, 1) + 7 - first_wday) % 7;
+        let cell = row * 7 + col;
+        if cell < first_col {
+            return None;
+        }
+        match Date::from_proleptic_gregorian(year, month, cell - first_col + 1, 0, 0, 0, 0) {
+            Ok(d) => Some(d),
+            Err(_) => None
+        }
+    }
+
+    /**
+     * Allocates a Date object from a number of seconds, milliseconds,
+     * microseconds or nanoseconds since epoch, negative before it. Precision
+     * below the millisecond is dropped, rounding down. Returns None if the
+     * seconds overflow in milliseconds or the date is before year 0.
+     */
+    pub fn from_epoch_with_unit(value: i64, unit: EpochUnit) -> Option<Date> {
+        let ms = match unit {
+            Seconds => value.checked_mul(&1000),
+            Millis => Some(value),
+            Micros => Some(value.div_floor(&1000)),
+            Nanos => Some(value.div_floor(&1000000))
+        };
+        match ms {
+            Some(ms) => Date::from_timestamp_millis_checked(ms),
+            None => None
+        }
+    }
+
+    /**
+     * Allocates a Date object from a number since epoch of unknown unit,
+     * guessing the unit from its magnitude. Values below 1e10 are taken as
+     * seconds, below 1e13 as milliseconds, below 1e16 as microseconds and
+     * anything larger as nanoseconds. Each range covers dates from 1970 up
+     * to the year 2286, and for negative values from 1653 to 1970. Returns
+     * None as from_epoch_with_unit.
+     */
+    pub fn from_epoch_auto(value: i64) -> Option<Date> {
+        let magnitude = value.abs();
+        let unit = if magnitude < 10000000000 {
+            Seconds
+        } else if magnitude < 10000000000000 {
+            Millis
+        } else if magnitude < 10000000000000000 {
+            Micros
+        } else {
+            Nanos
+        };
+        Date::from_epoch_with_unit(value, unit)
+    }
+
+    #[deprecated="use `from_timestamp_millis` instead"]
+    pub fn from_epoch(epoch_date: i64) -> Date {
+        Date::from_timestamp_millis(epoch_date)
+    }
+
+    /**
+     * Allocates a Date object from a Unix timestamp, in seconds since epoch,
+     * or returns None if the milliseconds do not fit in an i64 or the date
+     * is before year 0.
+     */
+    pub fn from_unix_timestamp(secs: i64) -> Option<Date> {
+        match secs.checked_mul(&1000) {
+            Some(ms) => Date::from_timestamp_millis_checked(ms),
+            None => None
         }
     }
 
+    /**
+     * Returns the Unix timestamp of the date, in whole seconds since epoch.
+     * Dates before epoch round down, so 1969-12-31 23:59:59.500 is -1.
+     */
+    pub fn unix_timestamp(&self) -> i64 {
+        self.since_epoch.div_floor(&1000)
+    }
+
+    /**
+     * Allocates a Date object from nanoseconds since epoch. Precision below
+     * the millisecond is dropped.
+     */
+    pub fn from_unix_timestamp_nanos(nanos: i64) -> Date {
+        Date::from_timestamp_millis(nanos.div_floor(&1000000))
+    }
+
+    /**
+     * Returns the nanoseconds since epoch of the date, or None outside the
+     * years 1677 to 2262, which do not fit in an i64.
+     */
+    pub fn unix_timestamp_nanos(&self) -> Option<i64> {
+        self.since_epoch.checked_mul(&1000000)
+    }
+
     /**
     * Allocates a Date object and initializes it to represent the current time.
     * For now time is in UTC
     */
     pub fn now() -> Date {
-        let ts = time::get_time();
-        let sec = (ts.sec * 1000) as uint;
-        let msec = (ts.nsec / 1000000) as uint;
+        Date::from_timespec(time::get_time())
+    }
+
+    /**
+     * Allocates a Date object from a Timespec, the time since epoch used by
+     * extra::time. Precision below the millisecond is dropped.
+     */
+    pub fn from_timespec(ts: Timespec) -> Date {
+        let sec = ts.sec * 1000;
+        let msec = (ts.nsec / 1000000) as i64;
 
-        Date::from_epoch(sec + msec)
+        Date::from_timestamp_millis(sec + msec)
     }
 
     /**
-    * Returns the number of milliseconds since the 1st of January, 1970,
-    * 00:00:00 GMT represented by this Date object.
-    */
-    pub fn get_time(&self) -> uint {
-        self.since_epoch
+     * Allocates a Date object from seconds and nanoseconds since epoch, the
+     * parts a SystemTime duration since epoch splits into. Precision below
+     * the millisecond is dropped. Times that overflow in milliseconds or
+     * are before year 0 give InvalidYear.
+     */
+    pub fn from_secs_nanos(secs: i64, nanos: u32) -> Result<Date, DateError> {
+        if nanos >= 1000000000 {
+            return Err(InvalidNanosecond);
+        }
+        let date = match secs.checked_mul(&1000) {
+            Some(ms) => match ms.checked_add(&((nanos / 1000000) as i64)) {
+                Some(ms) => Date::from_timestamp_millis_checked(ms),
+                None => None
+            },
+            None => None
+        };
+        match date {
+            Some(d) => Ok(d),
+            None => Err(InvalidYear)
+        }
     }
 
     /**
-    * Returns the Calendar object represented by this Date object.
-    */
-    pub fn get_cal(&self) -> GCalendar {
-        self.gcal
+     * Returns the time since epoch of this Date object as a Timespec.
+     */
+    pub fn to_timespec(&self) -> Timespec {
+        millis_to_timespec(self.since_epoch)
     }
 
     /**
-    * Formats the represented time according to the format string.
-    */
-    pub fn strftime(&self, format: &str) -> ~str {
-        let mut buf = ~"";
+     * Returns the time elapsed since an earlier date, or None if the given
+     * date is later than this one.
+     */
+    pub fn elapsed_since(&self, earlier: &Date) -> Option<Timespec> {
+        if self.since_epoch < earlier.since_epoch {
+            None
+        } else {
+            Some(millis_to_timespec(self.since_epoch - earlier.since_epoch))
+        }
+    }
 
-        do io::with_str_reader(format) |rdr| {
-            while !rdr.eof() {
-                match rdr.read_char() {
-                    '%' => buf.push_str(self.get_cal().get_date(rdr.read_char())),
-                    ch => buf.push_char(ch)
-                }
-            }
+    /**
+     * Returns the date moved forward by the duration, or None if that
+     * overflows or goes before year 0.
+     */
+    pub fn checked_add(&self, duration: &Duration) -> Option<Date> {
+        match self.since_epoch.checked_add(&duration.as_millis()) {
+            Some(ms) => Date::from_timestamp_millis_checked(ms),
+            None => None
         }
+    }
 
-        buf
+    /**
+     * Returns the date moved back by the duration, or None if that
+     * overflows or goes before year 0.
+     */
+    pub fn checked_sub(&self, duration: &Duration) -> Option<Date> {
+        match duration.as_millis().checked_mul(&-1) {
+            Some(ms) => self.checked_add(&Duration::from_millis(ms)),
+            None => None
+        }
     }
 
     /**
-    * Formats the current time according to the format string.
-    */
-    pub fn now_strftime(format: &str) -> ~str {
-        let d = Date::now();
-        d.strftime(format)
+     * Returns the signed number of milliseconds from the given reference
+     * date to this one. Negative if this date is earlier.
+     */
+    pub fn millis_since_epoch(&self, epoch: &Date) -> i64 {
+        self.since_epoch - epoch.since_epoch
     }
 
     /**
-     * Returns a time string formatted according to ISO 8601.
-     *
-     * utc:   "2012-02-22T14:53:18Z"
+     * Returns the signed number of whole days from the given reference date
+     * to this one, rounded down. A date half a day before the reference is
+     * day -1.
      */
-    pub fn iso_format(&self) -> ~str {
-        self.strftime("%Y-%m-%d %H:%M:%S")
+    pub fn days_since_epoch(&self, epoch: &Date) -> i64 {
+        self.millis_since_epoch(epoch).div_floor(&86400000)
     }
 
     /**
-     * Return a string of the current time in the form
-     * "Thu Jan  1 00:00:00 1970".
+     * Returns the number of calendar days from the given date to this one,
+     * times of day ignored, negative if the given date is later. 23:00 on
+     * Dec 31 to 01:00 on Jan 1 is one day.
      */
-    pub fn ctime(&self) -> ~str {
-        self.strftime("%c")
+    pub fn days_since(&self, other: &Date) -> i64 {
+        self.day_of_epoch() - other.day_of_epoch()
     }
 
     /**
-     * Returns a time string formatted according to RFC 822.
-     *
-     * utc:   "Thu, 22 Mar 2012 14:53:18 UTC"
+     * Returns the number of calendar days from this date to the given one,
+     * times of day ignored, negative if the given date is earlier.
      */
-    pub fn rfc822(&self) -> ~str {
-        self.strftime("%a, %d %b %Y %T UTC")
+    pub fn days_until(&self, other: &Date) -> i64 {
+        other.days_since(self)
     }
 
     /**
-     * Returns a time string formatted according to RFC 822 with Zulu time.
-     *
-     * utc:   "Thu, 22 Mar 2012 14:53:18 -0000"
+     * Returns the number of calendar days touched by the half-open range
+     * [start, end), a partial day counting as a whole one. Unlike
+     * days_since_epoch, Jan 1 at 23:59 to Jan 3 at 00:01 is 3 days.
      */
-    pub fn rfc822z(&self) -> ~str {
-        self.strftime("%a, %d %b %Y %T %z")
+    pub fn days_in_range(start: &Date, end: &Date) -> u64 {
+        if end.since_epoch <= start.since_epoch {
+            return 0;
+        }
+        let first = start.day_of_epoch();
+        let last = Date::from_timestamp_millis(end.since_epoch - 1).day_of_epoch();
+        (last - first + 1) as u64
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::Date;
+    /**
+     * Returns the number of whole days since epoch, rounded down, so every
+     * time of a day gets the same number.
+     */
+    pub fn day_of_epoch(&self) -> i64 {
+        self.days_since_epoch(&Date::unix_epoch())
+    }
 
-    #[test]
-    fn from_epoch() {
-        let d = Date::from_epoch(433166421023);
-        assert_eq!(d.get_time(), 433166421023);
+    /**
+     * Allocates a Date object at midnight of the given day since epoch.
+     */
+    pub fn from_day_of_epoch(day: i64) -> Date {
+        Date::from_timestamp_millis(day * 86400000)
     }
 
-    #[test]
-    fn now() {
-        println("now: " + Date::now_strftime("%Y-%m-%d %H:%M:%S"));
-        assert_eq!(true, true);
+    /**
+     * Returns the Julian Day Number of the day, the days since the 1st of
+     * January 4713 BC in the proleptic Julian calendar: 2440588 at epoch.
+     * Every time of a day gets the same number.
+     */
+    pub fn to_julian_day_number(&self) -> i64 {
+        self.day_of_epoch() + EPOCHJULIANDAY
     }
 
-    #[test]
-    fn test_strftime() {
-        let d = Date::from_epoch(1234567890543);
-        assert_eq!(d.strftime(""), ~"");
-        assert_eq!(d.strftime("%A"), ~"Friday");
-        assert_eq!(d.strftime("%a"), ~"Fri");
-        assert_eq!(d.strftime("%B"), ~"February");
-        assert_eq!(d.strftime("%b"), ~"Feb");
-        assert_eq!(d.strftime("%C"), ~"20");
-        assert_eq!(d.strftime("%c"), ~"Fri Feb 13 23:31:30 2009");
-        assert_eq!(d.strftime("%D"), ~"02/13/09");
-        assert_eq!(d.strftime("%d"), ~"13");
-        assert_eq!(d.strftime("%e"), ~"13");
-        assert_eq!(d.strftime("%f"), ~"000000030");
-        assert_eq!(d.strftime("%F"), ~"2009-02-13");
-        assert_eq!(d.strftime("%G"), ~"2009");
-        assert_eq!(d.strftime("%g"), ~"09");
-        assert_eq!(d.strftime("%H"), ~"23");
-        assert_eq!(d.strftime("%I"), ~"11");
-        assert_eq!(d.strftime("%j"), ~"044");
-        assert_eq!(d.strftime("%k"), ~"23");
-        assert_eq!(d.strftime("%l"), ~"11");
-        assert_eq!(d.strftime("%M"), ~"31");
-        assert_eq!(d.strftime("%m"), ~"02");
-        assert_eq!(d.strftime("%n"), ~"\n");
-        assert_eq!(d.strftime("%P"), ~"pm");
-        assert_eq!(d.strftime("%p"), ~"PM");
-        assert_eq!(d.strftime("%R"), ~"23:31");
-        assert_eq!(d.strftime("%r"), ~"11:31:30 PM");
-        assert_eq!(d.strftime("%S"), ~"30");
-        assert_eq!(d.strftime("%s"), ~"1234567890");
-        assert_eq!(d.strftime("%T"), ~"23:31:30");
-        assert_eq!(d.strftime("%t"), ~"\t");
-        assert_eq!(d.strftime("%U"), ~"06");
-        assert_eq!(d.strftime("%u"), ~"5");
-        assert_eq!(d.strftime("%V"), ~"07");
-        assert_eq!(d.strftime("%v"), ~"13-Feb-2009");
-        assert_eq!(d.strftime("%W"), ~"06");
-        assert_eq!(d.strftime("%w"), ~"5");
-        assert_eq!(d.strftime("%Y"), ~"2009");
-        assert_eq!(d.strftime("%y"), ~"09");
-        assert_eq!(d.strftime("%z"), ~"-0000");
-        assert_eq!(d.strftime("%%"), ~"%");
+    /**
+     * Allocates a Date object at midnight of the day of the given Julian Day
+     * Number. Days before year 0, JDN 1721060, are not supported.
+     */
+    pub fn from_julian_day_number(jdn: i64) -> Date {
+        Date::from_day_of_epoch(jdn - EPOCHJULIANDAY)
+    }
 
-        assert_eq!(d.iso_format(), ~"2009-02-13 23:31:30");
-        assert_eq!(d.ctime(), ~"Fri Feb 13 23:31:30 2009");
-        assert_eq!(d.rfc822z(), ~"Fri, 13 Feb 2009 23:31:30 -0000");
-        assert_eq!(d.rfc822z(), ~"Fri, 13 Feb 2009 23:31:30 -0000");
+    /**
+     * Returns the number of calendar months since January 1970, 0 being
+     * January 1970. Dates in the same month get the same number.
+     */
+    pub fn months_since_epoch(&self) -> i64 {
+        (self.gcal.year as i64 - 1970) * 12 + self.gcal.month as i64 - 1
+    }
+
+    /**
+     * Allocates a Date object at midnight of the first day of the given
+     * month since epoch.
+     */
+    pub fn from_months_since_epoch(months: i64) -> Date {
+        let year = (1970 + months.div_floor(&12)) as uint;
+        let month = months.mod_floor(&12) as uint + 1;
+        Date::from_calendar(year, month, 1, 0, 0, 0, 0)
+    }
+
+    /**
+     * Returns the calendar quarter [1-4] of the date: 1 for January to
+     * March, up to 4 for October to December.
+     */
+    pub fn quarter(&self) -> u32 {
+        ((self.gcal.month - 1) / 3 + 1) as u32
+    }
+
+    /**
+     * Returns the week of the year [0-53] as with %U: weeks start on Sunday,
+     * and the days before the first Sunday are in week 0.
+     */
+    pub fn week_of_year(&self) -> u32 {
+        ((self.gcal.yday + 7 - self.gcal.wday) / 7) as u32
+    }
+
+    /**
+     * Returns the week of the year [0-53] as with %W: weeks start on Monday,
+     * and the days before the first Monday are in week 0.
+     */
+    pub fn week_of_year_monday(&self) -> u32 {
+        ((self.gcal.yday + 7 - (self.gcal.wday + 6) % 7) / 7) as u32
+    }
+
+    /**
+     * Returns the ISO 8601 week number [1-53] as with %V. The first days of
+     * January may be in the last week of the previous year, and the last
+     * days of December in week 1 of the next one.
+     */
+    pub fn iso_week_number(&self) -> u32 {
+        let (_, week) = self.gcal.iso_year_week();
+        week as u32
+    }
+
+    /**
+     * Returns midnight of the first day of the calendar quarter: the 1st of
+     * January, April, July or October.
+     */
+    pub fn quarter_start(&self) -> Date {
+        self.fiscal_quarter_start(1)
+    }
+
+    /**
+     * Returns the last millisecond of the calendar quarter: the 31st of
+     * March, 30th of June, 30th of September or 31st of December at
+     * 23:59:59.999.
+     */
+    pub fn quarter_end(&self) -> Date {
+        let start = self.quarter_start().months_since_epoch();
+        let next = Date::from_months_since_epoch(start + 3);
+        Date::from_timestamp_millis(next.since_epoch - 1)
+    }
+
+    /**
+     * Returns midnight of the first day of the quarter of a fiscal year
+     * starting in the given month [1-12].
+     */
+    pub fn fiscal_quarter_start(&self, fy_start_month: uint) -> Date {
+        assert!(fy_start_month >= 1 && fy_start_month <= 12);
+        let months = self.months_since_epoch();
+        let offset = ((fy_start_month - 1) % 3) as i64;
+        let back = (months - offset).mod_floor(&3);
+        Date::from_months_since_epoch(months - back)
+    }
+
+    /**
+     * Returns midnight of the same day.
+     */
+    pub fn beginning_of_day(&self) -> Date {
+        Date::from_calendar(self.gcal.year, self.gcal.month, self.gcal.mday, 0, 0, 0, 0)
+    }
+
+    /**
+     * Returns 23:59:59 of the same day.
+     */
+    pub fn end_of_day(&self) -> Date {
+        Date::from_calendar(self.gcal.year, self.gcal.month, self.gcal.mday, 23, 59, 59, 0)
+    }
+
+    /**
+     * Returns midnight of the first day of the month.
+     */
+    pub fn beginning_of_month(&self) -> Date {
+        Date::from_calendar(self.gcal.year, self.gcal.month, 1, 0, 0, 0, 0)
+    }
+
+    /**
+     * Returns 23:59:59 of the last day of the month.
+     */
+    pub fn end_of_month(&self) -> Date {
+        let last = self.gcal.days_in_current_month() as uint;
+        Date::from_calendar(self.gcal.year, self.gcal.month, last, 23, 59, 59, 0)
+    }
+
+    /**
+     * Returns the number of weeks, Monday to Sunday, since the week of the
+     * epoch. Week 0 starts on Monday the 29th of December 1969, so dates in
+     * the same ISO week get the same number.
+     */
+    pub fn weeks_since_epoch(&self) -> i64 {
+        (self.day_of_epoch() + 3).div_floor(&7)
+    }
+
+    /**
+     * Allocates a Date object at midnight of the Monday starting the given
+     * week since epoch.
+     */
+    pub fn from_weeks_since_epoch(weeks: i64) -> Date {
+        Date::from_timestamp_millis((weeks * 7 - 3) * 86400000)
+    }
+
+    /**
+     * Returns the milliseconds since epoch as 8 big-endian bytes, in two's
+     * complement for dates before epoch.
+     */
+    pub fn to_epoch_millis_u64_be(&self) -> [u8, ..8] {
+        let ms = self.since_epoch as u64;
+        let mut bytes = [0u8, ..8];
+        for i in range(0u, 8) {
+            bytes[i] = (ms >> (56 - 8 * i)) as u8;
+        }
+        bytes
+    }
+
+    /**
+     * Allocates a Date object from milliseconds since epoch stored as 8
+     * big-endian bytes.
+     */
+    pub fn from_epoch_millis_u64_be(bytes: &[u8, ..8]) -> Date {
+        let mut ms = 0u64;
+        for &b in bytes.iter() {
+            ms = ms << 8 | b as u64;
+        }
+        Date::from_timestamp_millis(ms as i64)
+    }
+
+    /**
+     * Returns the whole seconds since epoch as 4 big-endian bytes, for
+     * legacy formats. Dates after 2106-02-07 06:28:15 saturate to that time
+     * and dates before epoch to epoch.
+     */
+    pub fn to_epoch_secs_u32_be(&self) -> [u8, ..4] {
+        let secs = self.since_epoch.div_floor(&1000);
+        let secs = if secs < 0 {
+            0
+        } else if secs > 0xffffffff {
+            0xffffffff
+        } else {
+            secs as u32
+        };
+        let mut bytes = [0u8, ..4];
+        for i in range(0u, 4) {
+            bytes[i] = (secs >> (24 - 8 * i)) as u8;
+        }
+        bytes
+    }
+
+    /**
+     * Allocates a Date object from seconds since epoch stored as 4
+     * big-endian bytes.
+     */
+    pub fn from_epoch_secs_u32_be(bytes: &[u8, ..4]) -> Date {
+        let mut secs = 0i64;
+        for &b in bytes.iter() {
+            secs = secs << 8 | b as i64;
+        }
+        Date::from_timestamp_millis(secs * 1000)
+    }
+
+    /**
+     * Returns the seconds since epoch in scientific notation, with six
+     * decimals.
+     *
+     * "1.234568e9 s"
+     */
+    pub fn format_epoch_sci(&self) -> ~str {
+        let secs = self.since_epoch as f64 / 1000.0;
+        let mut mantissa = secs.abs();
+        let mut exponent = 0;
+        while mantissa >= 10.0 {
+            mantissa /= 10.0;
+            exponent += 1;
+        }
+        while mantissa != 0.0 && mantissa < 1.0 {
+            mantissa *= 10.0;
+            exponent -= 1;
+        }
+        /* Rounding may carry up to 10, as in 9.9999999 */
+        let mut digits = format!("{:.6f}", mantissa);
+        if digits == ~"10.000000" {
+            digits = format!("{:.6f}", mantissa / 10.0);
+            exponent += 1;
+        }
+        let sign = if secs < 0.0 { "-" } else { "" };
+        format!("{}{}e{} s", sign, digits, exponent)
+    }
+
+    /**
+     * Returns the seconds since epoch rounded to a short figure with a
+     * K (thousand), M (million), B (billion) or T (trillion) suffix.
+     *
+     * "1.23B s"
+     */
+    pub fn format_epoch_human(&self) -> ~str {
+        let secs = self.since_epoch as f64 / 1000.0;
+        for &(suffix, size) in [("T", 1e12), ("B", 1e9), ("M", 1e6), ("K", 1e3)].iter() {
+            if secs.abs() >= size {
+                return format!("{:.2f}{} s", secs / size, suffix);
+            }
+        }
+        format!("{:.2f} s", secs)
+    }
+
+    /**
+     * Formats the date the way NetCDF and the CF Conventions store time:
+     * "N unit since YYYY-MM-DD HH:MM:SS", where N is the whole number of
+     * units from the reference date, rounded down. The unit is "seconds",
+     * "hours" or "days"; any other unit gives None.
+     */
+    pub fn to_netcdf_string(&self, unit: &str, reference: &Date) -> Option<~str> {
+        let size = match netcdf_unit_millis(unit) {
+            Some(size) => size,
+            None => return None
+        };
+        let count = self.millis_since_epoch(reference).div_floor(&size);
+        Some(format!("{} {} since {}", count, unit,
+                     reference.strftime("%Y-%m-%d %H:%M:%S")))
+    }
+
+    /**
+     * Reads a NetCDF time such as "36 hours since 2009-02-13 00:00:00".
+     */
+    pub fn from_netcdf_string(s: &str) -> Result<Date, ParseError> {
+        let count_end = match s.find(' ') {
+            Some(i) => i,
+            None => return Err(parse::InvalidFormat)
+        };
+        let count = match from_str::<i64>(s.slice_to(count_end)) {
+            Some(n) => n,
+            None => return Err(parse::InvalidFormat)
+        };
+
+        let rest = s.slice_from(count_end + 1);
+        let unit_end = match rest.find(' ') {
+            Some(i) => i,
+            None => return Err(parse::InvalidFormat)
+        };
+        let size = match netcdf_unit_millis(rest.slice_to(unit_end)) {
+            Some(size) => size,
+            None => return Err(parse::InvalidValue)
+        };
+
+        let rest = rest.slice_from(unit_end + 1);
+        if !rest.starts_with("since ") {
+            return Err(parse::InvalidFormat);
+        }
+        let reference = match parse::parse(rest.slice_from(6), "%Y-%m-%d %H:%M:%S") {
+            Ok(p) => p,
+            Err(e) => return Err(e)
+        };
+        let reference = match Date::from_parsed(&reference) {
+            Ok(d) => d,
+            Err(e) => return Err(e)
+        };
+
+        Ok(Date::from_timestamp_millis(reference.since_epoch + count * size))
+    }
+
+    /**
+     * Returns the Unix epoch, 1st of January, 1970, 00:00:00 UTC.
+     */
+    pub fn unix_epoch() -> Date {
+        Date::from_timestamp_millis(0)
+    }
+
+    /**
+     * Returns the GPS epoch, 6th of January, 1980, 00:00:00 UTC.
+     */
+    pub fn gps_epoch() -> Date {
+        Date::from_timestamp_millis(315964800000)
+    }
+
+    /**
+     * Returns the J2000 astronomical epoch, 1st of January, 2000, 12:00:00.
+     */
+    pub fn j2000() -> Date {
+        Date::from_timestamp_millis(946728000000)
+    }
+
+    /**
+     * Returns the NTP epoch, 1st of January, 1900, 00:00:00 UTC.
+     */
+    pub fn ntp_epoch() -> Date {
+        Date::from_timestamp_millis(-2208988800000)
+    }
+
+    /**
+     * Reads a "YYYY-MM-DD HH:MM:SS" local time in the given timezone and
+     * converts it to UTC. The timezone is either a fixed offset such as
+     * "+05:30" or a name such as "Asia/Kolkata".
+     *
+     * Named timezones always get their standard time offset, daylight
+     * saving time is not applied.
+     */
+    pub fn from_str_with_tz(date_str: &str, tz_str: &str)
+                            -> Result<Date, ParseError> {
+        let mut fields = match parse::parse(date_str, "%Y-%m-%d %H:%M:%S") {
+            Ok(p) => p,
+            Err(e) => return Err(e)
+        };
+        fields.offset = match parse::parse_timezone(tz_str) {
+            Ok(offset) => offset,
+            Err(e) => return Err(e)
+        };
+
+        Date::from_parsed(&fields)
+    }
+
+    /**
+     * Reads a date according to a strftime format string. The supported
+     * specifiers are %Y, %m, %d, %e, %H, %M, %S, %T, %j (day of the year),
+     * %s (seconds since epoch), %z, %Z, %%, and the names %A, %a, %B and %b.
+     * Fields missing in the format are taken from the epoch; %s sets the
+     * whole date by itself.
+     *
+     * Out of range fields give InvalidValue, unknown specifiers
+     * UnknownSpecifier, and input that does not follow the format or is left
+     * over UnexpectedInput, with the position where reading stopped.
+     */
+    pub fn strptime(s: &str, format: &str) -> Result<Date, ParseError> {
+        let mut p = match parse::parse_with_position(s, format) {
+            Ok(p) => p,
+            Err(e) => return Err(e)
+        };
+
+        match p.timestamp {
+            Some(secs) => return Ok(Date::from_timestamp_millis(secs * 1000)),
+            None => ()
+        }
+        if p.yday != 0 {
+            match GCalendar::from_ordinal_date(p.year, p.yday) {
+                Ok(cal) => {
+                    p.month = cal.month;
+                    p.mday = cal.mday;
+                }
+                Err(_) => return Err(parse::InvalidValue)
+            }
+        }
+        Date::from_parsed(&p)
+    }
+
+    /*
+     * Allocates a Date object from the fields read by the parser.
+     */
+    fn from_parsed(p: &Parsed) -> Result<Date, ParseError> {
+        let local = match Date::from_proleptic_gregorian(p.year, p.month, p.mday,
+                                                         p.hour, p.min, p.sec, 0) {
+            Ok(d) => d.since_epoch,
+            Err(_) => return Err(parse::InvalidValue)
+        };
+
+        Ok(Date::from_timestamp_millis(local - p.offset as i64 * 1000))
+    }
+
+    /**
+    * Returns the number of milliseconds since the 1st of January, 1970,
+    * 00:00:00 GMT represented by this Date object, negative for dates before
+    * it.
+    */
+    pub fn get_time(&self) -> i64 {
+        self.since_epoch
+    }
+
+    /**
+    * Returns the Calendar object represented by this Date object.
+    */
+    pub fn get_cal(&self) -> GCalendar {
+        self.gcal
+    }
+
+    /**
+     * Returns the month [1-12].
+     */
+    pub fn month_number(&self) -> uint {
+        self.gcal.month
+    }
+
+    /**
+     * Returns the full English name of the month.
+     */
+    pub fn month_name(&self) -> &'static str {
+        self.gcal.month_name()
+    }
+
+    /**
+     * Returns the three letter abbreviation of the month.
+     */
+    pub fn month_name_abbrev(&self) -> &'static str {
+        self.gcal.month_name_short()
+    }
+
+    /**
+     * Returns the day of the week [0-6], Sunday being 0.
+     */
+    pub fn weekday_number(&self) -> uint {
+        self.gcal.wday
+    }
+
+    /**
+     * Returns the full English name of the day of the week.
+     */
+    pub fn weekday_name(&self) -> &'static str {
+        self.gcal.day_name()
+    }
+
+    /**
+     * Returns the three letter abbreviation of the day of the week.
+     */
+    pub fn weekday_name_abbrev(&self) -> &'static str {
+        self.gcal.day_name_short()
+    }
+
+    /**
+     * Returns true on Saturday and Sunday.
+     */
+    pub fn is_weekend(&self) -> bool {
+        self.gcal.wday == 0 || self.gcal.wday == 6
+    }
+
+    /**
+     * Returns true from Monday to Friday.
+     */
+    pub fn is_weekday(&self) -> bool {
+        !self.is_weekend()
+    }
+
+    /**
+     * Returns the same time of the next day from Monday to Friday, skipping
+     * the weekend: Friday and Saturday go to Monday.
+     */
+    pub fn next_weekday(&self) -> Date {
+        self.plus_business_days(1).unwrap()
+    }
+
+    /**
+    * Formats the represented time according to the format string. An
+    * unknown specifier is written as an error message in its place, see
+    * strftime_checked to catch it instead.
+    */
+    pub fn strftime(&self, format: &str) -> ~str {
+        CompiledFormat::new(format).format(&self.gcal)
+    }
+
+    /**
+     * Like strftime, but fails with UnknownSpecifier if the format has a
+     * specifier strftime does not know, as in "%Q".
+     */
+    pub fn strftime_checked(&self, format: &str) -> Result<~str, FormatError> {
+        let f = CompiledFormat::new(format);
+        match f.check_known() {
+            Ok(()) => Ok(f.format(&self.gcal)),
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+     * Like strftime, also taking the POSIX era specifiers: %EC gives the
+     * era, "AD" or "BC", %Ey the year within the era and %EY both, as in
+     * "2009 AD". Year 0 is 1 BC. %E before any other specifier is ignored.
+     * Fails with UnknownSpecifier as strftime_checked.
+     */
+    pub fn strftime_with_era(&self, format: &str) -> Result<~str, FormatError> {
+        let (era, year) = if self.gcal.year >= 1 {
+            ("AD", self.gcal.year)
+        } else {
+            ("BC", 1 - self.gcal.year)
+        };
+
+        /* The letter after %E is compiled as the start of the next literal */
+        let compiled = CompiledFormat::new(format);
+        let mut items = ~[];
+        let mut iter = compiled.get_items().iter();
+        loop {
+            match iter.next() {
+                None => break,
+                Some(&Specifier('E')) => match iter.next() {
+                    Some(&Literal(ref text)) => {
+                        let (ch, rest) = text.slice_shift_char();
+                        items.push(match ch {
+                            'C' => Literal(era.to_owned()),
+                            'y' => Literal(year.to_str()),
+                            'Y' => Literal(format!("{:04u} {}", year, era)),
+                            ch => Specifier(ch)
+                        });
+                        if !rest.is_empty() {
+                            items.push(Literal(rest.to_owned()));
+                        }
+                    }
+                    Some(item) => {
+                        items.push(Specifier('E'));
+                        items.push(item.clone());
+                    }
+                    None => items.push(Specifier('E'))
+                },
+                Some(item) => items.push(item.clone())
+            }
+        }
+
+        let f = CompiledFormat::from_items(items);
+        match f.check_known() {
+            Ok(()) => Ok(f.format(&self.gcal)),
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+     * Like strftime, but fails with UnexpectedSpecifier if the format shows
+     * any part of the date.
+     */
+    pub fn strftime_time_only(&self, format: &str) -> Result<~str, FormatError> {
+        let f = CompiledFormat::new(format);
+        match f.check_time_only() {
+            Ok(()) => Ok(f.format(&self.gcal)),
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+     * Like strftime, but fails with UnexpectedSpecifier if the format shows
+     * any part of the time of day.
+     */
+    pub fn strftime_date_only(&self, format: &str) -> Result<~str, FormatError> {
+        let f = CompiledFormat::new(format);
+        match f.check_date_only() {
+            Ok(()) => Ok(f.format(&self.gcal)),
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+     * Returns the decade, the year divided by ten: 200 for 2000 to 2009.
+     */
+    pub fn decade(&self) -> uint {
+        self.gcal.year / 10
+    }
+
+    /**
+     * Returns the millennium, counted the historical way like
+     * GCalendar::century: the 2nd millennium goes from 1001 to 2000.
+     */
+    pub fn millennium(&self) -> uint {
+        (self.gcal.year + 999) / 1000
+    }
+
+    /**
+     * Returns true if the year is a multiple of 100.
+     */
+    pub fn is_century_year(&self) -> bool {
+        self.gcal.year % 100 == 0
+    }
+
+    /**
+     * Returns true if the year of the date is a leap year.
+     */
+    pub fn is_leap_year(&self) -> bool {
+        is_leap_year(self.gcal.year)
+    }
+
+    /**
+     * Returns the number of days, 365 or 366, in the year of the date.
+     */
+    pub fn num_days_in_year(&self) -> uint {
+        year_size(self.gcal.year)
+    }
+
+    /**
+     * Returns true if the year is a multiple of 1000.
+     */
+    pub fn is_millennium_year(&self) -> bool {
+        self.gcal.year % 1000 == 0
+    }
+
+    /**
+     * Returns the last two digits of the year.
+     */
+    pub fn years_since_century_start(&self) -> uint {
+        self.gcal.year % 100
+    }
+
+    /**
+     * Returns the date in the Bahá'í calendar, with Naw-Rúz on the 21st of
+     * March, or None before the first Naw-Rúz, the 21st of March 1844.
+     */
+    pub fn to_bahai(&self) -> Option<BahaiDate> {
+        let (year, month, mday) = (self.gcal.year, self.gcal.month, self.gcal.mday);
+        if year < 1844 || (year == 1844 && (month < 3 || (month == 3 && mday < 21))) {
+            return None;
+        }
+        Some(BahaiDate::from_gregorian(year, month, mday))
+    }
+
+    /**
+     * Allocates a Date object from a decimal year, the year plus the
+     * elapsed fraction of it: 2009.5 is noon on the 2nd of July 2009.
+     */
+    pub fn from_decimal_date(decimal_year: f64) -> Result<Date, DateError> {
+        let year = decimal_year.floor();
+        if !(year >= 0.0) {
+            return Err(InvalidYear);
+        }
+
+        let year = year as uint;
+        let start = Date::from_calendar(year, 1, 1, 0, 0, 0, 0);
+        let year_ms = (year_size(year) * 86400000) as f64;
+        let ms = ((decimal_year - year as f64) * year_ms).round() as i64;
+        Ok(Date::from_timestamp_millis(start.since_epoch + ms))
+    }
+
+    /**
+     * Returns the date as a decimal year, the year plus the elapsed fraction
+     * of it.
+     */
+    pub fn to_decimal_date(&self) -> f64 {
+        let year = self.gcal.year;
+        let start = Date::from_calendar(year, 1, 1, 0, 0, 0, 0);
+        let year_ms = (year_size(year) * 86400000) as f64;
+        year as f64 + (self.since_epoch - start.since_epoch) as f64 / year_ms
+    }
+
+    /**
+     * Allocates a Date object at midnight of the given Bahá'í date.
+     */
+    pub fn from_bahai(bd: BahaiDate) -> Result<Date, DateError> {
+        let (year, month, day) = bd.to_gregorian();
+        Date::from_proleptic_gregorian(year, month, day, 0, 0, 0, 0)
+    }
+
+    /**
+     * Returns the dates on the given month and day of every year, within 60
+     * years of the current one, named by the given stem and branch of the
+     * Chinese sexagenary cycle. Years where the day does not exist are left
+     * out.
+     */
+    pub fn from_stem_branch_year(stem: HeavenlyStem, branch: EarthlyBranch,
+                                 month: uint, day: uint) -> ~[Date] {
+        let year = Date::now().gcal.year;
+        Date::from_stem_branch_year_around(stem, branch, month, day, year)
+    }
+
+    fn from_stem_branch_year_around(stem: HeavenlyStem, branch: EarthlyBranch,
+                                    month: uint, day: uint, year: uint) -> ~[Date] {
+        let years = chinese::years_with_stem_branch(stem, branch, year - 60, year + 60);
+        let mut dates = ~[];
+        for &y in years.iter() {
+            match Date::from_proleptic_gregorian(y, month, day, 0, 0, 0, 0) {
+                Ok(d) => dates.push(d),
+                Err(_) => ()
+            }
+        }
+        dates
+    }
+
+    /**
+     * Formats each of the dates according to the same format string, which is
+     * only read once.
+     */
+    pub fn strftime_n(dates: &[Date], format: &str) -> ~[~str] {
+        let compiled = CompiledFormat::new(format);
+        dates.iter().map(|d| compiled.format(&d.gcal)).collect()
+    }
+
+    /**
+    * Formats the current time according to the format string.
+    */
+    pub fn now_strftime(format: &str) -> ~str {
+        let d = Date::now();
+        d.strftime(format)
+    }
+
+    /**
+     * Returns a time string formatted according to ISO 8601.
+     *
+     * utc:   "2012-02-22T14:53:18Z"
+     */
+    pub fn iso_format(&self) -> ~str {
+        self.strftime("%Y-%m-%d %H:%M:%S")
+    }
+
+    /**
+     * Return a string of the current time in the form
+     * "Thu Jan  1 00:00:00 1970".
+     */
+    pub fn ctime(&self) -> ~str {
+        self.strftime("%c")
+    }
+
+    /**
+     * Reads the exact 24 characters written by C's asctime() and ctime(),
+     * "Thu Jan  1 00:00:00 1970", with or without the final newline.
+     */
+    pub fn from_ansi_c_asctime(s: &str) -> Result<Date, ParseError> {
+        let s = if s.ends_with("\n") { s.slice_to(s.len() - 1) } else { s };
+        /* The day of the month is padded with a space, never with a zero */
+        if s.len() != 24 || s[8] == '0' as u8 {
+            return Err(parse::InvalidFormat);
+        }
+
+        match parse::parse(s, "%a %b %e %H:%M:%S %Y") {
+            Ok(p) => Date::from_parsed(&p),
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+     * Reads a ctime() string with a 2-digit year, "Fri Feb 13 23:31:30 09",
+     * with or without the final newline. The year is taken in the 100 years
+     * starting at pivot_year: with 1970, 70-99 are 1970-1999 and 00-69 are
+     * 2000-2069; with 1950, 50-99 are 1950-1999 and 00-49 are 2000-2049.
+     */
+    pub fn from_ctime_2digit_year(s: &str, pivot_year: u32) -> Result<Date, ParseError> {
+        let s = if s.ends_with("\n") { s.slice_to(s.len() - 1) } else { s };
+        if s.len() != 22 || s[8] == '0' as u8 || s[20] == ' ' as u8 {
+            return Err(parse::InvalidFormat);
+        }
+
+        let mut p = match parse::parse(s, "%a %b %e %H:%M:%S %Y") {
+            Ok(p) => p,
+            Err(e) => return Err(e)
+        };
+        let pivot = pivot_year as uint;
+        p.year += pivot - pivot % 100;
+        if p.year < pivot {
+            p.year += 100;
+        }
+        Date::from_parsed(&p)
+    }
+
+    /**
+     * Reads the default output of the Unix date command in English,
+     * "Fri Feb 13 23:31:30 UTC 2009". The timezone abbreviation may be left
+     * out, and is applied with its standard time offset.
+     */
+    pub fn parse_unix_date(s: &str) -> Result<Date, ParseError> {
+        match parse::parse_first(s, &["%a %b %e %T %Z %Y", "%a %b %e %T %Y"]) {
+            Ok(p) => Date::from_parsed(&p),
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+     * Returns a time string formatted according to RFC 822.
+     *
+     * utc:   "Thu, 22 Mar 2012 14:53:18 UTC"
+     */
+    pub fn rfc822(&self) -> ~str {
+        self.strftime("%a, %d %b %Y %T UTC")
+    }
+
+    /**
+     * Returns a time string formatted according to RFC 822 with Zulu time.
+     *
+     * utc:   "Thu, 22 Mar 2012 14:53:18 +0000"
+     */
+    pub fn rfc822z(&self) -> ~str {
+        self.rfc2822()
+    }
+
+    /**
+     * Returns a time string formatted according to RFC 2822, as used in the
+     * Date header of email and MIME messages.
+     *
+     * utc:   "Thu, 22 Mar 2012 14:53:18 +0000"
+     */
+    pub fn rfc2822(&self) -> ~str {
+        self.strftime("%a, %d %b %Y %T %z")
+    }
+
+    /**
+     * Returns the ISO 8601 week date, "YYYY-Www-D".
+     *
+     * utc:   "2009-W07-5"
+     */
+    pub fn format_iso_week_date(&self) -> ~str {
+        self.strftime("%G-W%V-%u")
+    }
+
+    /**
+     * Reads an ISO 8601 week date, "YYYY-Www-D", giving midnight of that day.
+     * The week has to exist in the given week-based year.
+     */
+    pub fn parse_iso_week_date(s: &str) -> Result<Date, ParseError> {
+        let p = match parse::parse(s, "%G-W%V-%u") {
+            Ok(p) => p,
+            Err(e) => return Err(e)
+        };
+        if p.week_year < 1 || p.weekday < 1 || p.weekday > 7 {
+            return Err(parse::InvalidValue);
+        }
+
+        /* The 4th of January is always in the first week */
+        let jan4 = Date::from_calendar(p.week_year, 1, 4, 0, 0, 0, 0);
+        let jan4_wday = jan4.gcal.wday;
+        let jan1_wday = (jan4_wday + 4) % 7;
+        let weeks = if jan1_wday == 4 ||
+                       (jan1_wday == 3 && is_leap_year(p.week_year)) { 53 } else { 52 };
+        if p.week < 1 || p.week > weeks {
+            return Err(parse::InvalidValue);
+        }
+
+        let monday = jan4.since_epoch - (((jan4_wday + 6) % 7) * 86400000) as i64;
+        let day = monday + (((p.week - 1) * 7 + p.weekday - 1) * 86400000) as i64;
+        Ok(Date::from_timestamp_millis(day))
+    }
+
+    /**
+     * Returns the date as written in Atom feeds, RFC 3339 in UTC.
+     *
+     * utc:   "2009-02-13T23:31:30Z"
+     */
+    pub fn to_atom_date(&self) -> ~str {
+        self.rfc3339_seconds() + "Z"
+    }
+
+    /*
+     * The RFC 3339 date and time to the second, without the offset:
+     * "2009-02-13T23:31:30".
+     */
+    fn rfc3339_seconds(&self) -> ~str {
+        self.strftime("%Y-%m-%dT%H:%M:%S")
+    }
+
+    /**
+     * Returns the date as written in RSS feeds, RFC 822 in UTC.
+     *
+     * utc:   "Fri, 13 Feb 2009 23:31:30 +0000"
+     */
+    pub fn to_rss_date(&self) -> ~str {
+        self.rfc2822()
+    }
+
+    /**
+     * Returns the Atom <updated> element of the date.
+     *
+     * utc:   "<updated>2009-02-13T23:31:30Z</updated>"
+     */
+    pub fn to_atom_updated_element(&self) -> ~str {
+        format!("<updated>{}</updated>", self.to_atom_date())
+    }
+
+    /**
+     * Returns the RSS <pubDate> element of the date.
+     *
+     * utc:   "<pubDate>Fri, 13 Feb 2009 23:31:30 +0000</pubDate>"
+     */
+    pub fn to_rss_pubdate_element(&self) -> ~str {
+        format!("<pubDate>{}</pubDate>", self.to_rss_date())
+    }
+
+    /**
+     * Reads a date written in an Atom (RFC 3339) or RSS (RFC 822) feed. RFC
+     * 3339 dates may have a fraction of the second.
+     */
+    pub fn from_feed_date(s: &str) -> Result<Date, ParseError> {
+        match Date::from_rfc3339(s) {
+            Ok(d) => return Ok(d),
+            Err(_) => ()
+        }
+
+        match parse::parse_first(s, &["%Y-%m-%dT%H:%M:%S%z",
+                                     "%a, %d %b %Y %H:%M:%S %z",
+                                     "%a, %d %b %Y %H:%M:%S %Z",
+                                     "%d %b %Y %H:%M:%S %z",
+                                     "%d %b %Y %H:%M:%S %Z"]) {
+            Ok(p) => Date::from_parsed(&p),
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+     * Returns the date as an iCalendar (RFC 5545) UTC date-time, as used in
+     * DTSTART and DTEND.
+     *
+     * utc:   "20090213T233130Z"
+     */
+    pub fn to_ical_datetime(&self) -> ~str {
+        self.strftime("%Y%m%dT%H%M%SZ")
+    }
+
+    /**
+     * Reads an iCalendar (RFC 5545) date-time. "20090213T233130Z" is in UTC,
+     * "20090213T183130" is a local time in the given timezone, or in UTC if
+     * there is none. A "TZID=America/New_York:20090213T183130" parameter
+     * overrides the given timezone. As in from_str_with_tz, named timezones
+     * always get their standard time offset.
+     */
+    pub fn from_ical_datetime(s: &str, tz: Option<&str>) -> Result<Date, ParseError> {
+        let mut value = s;
+        let mut tz = tz;
+        if s.starts_with("TZID=") {
+            match s.find(':') {
+                Some(i) => {
+                    tz = Some(s.slice(5, i));
+                    value = s.slice_from(i + 1);
+                }
+                None => return Err(parse::InvalidFormat)
+            }
+        }
+
+        if value.ends_with("Z") {
+            return match parse::parse(value, "%Y%m%dT%H%M%SZ") {
+                Ok(p) => Date::from_parsed(&p),
+                Err(e) => Err(e)
+            };
+        }
+
+        let mut fields = match parse::parse(value, "%Y%m%dT%H%M%S") {
+            Ok(p) => p,
+            Err(e) => return Err(e)
+        };
+        match tz {
+            Some(name) => {
+                fields.offset = match parse::parse_timezone(name) {
+                    Ok(offset) => offset,
+                    Err(e) => return Err(e)
+                };
+            }
+            None => ()
+        }
+        Date::from_parsed(&fields)
+    }
+
+    /**
+     * Returns the date in compact Zulu (UTC) time, as used in military and
+     * aviation messages. Milliseconds are dropped.
+     *
+     * utc:   "20090213T233130Z"
+     */
+    pub fn to_zulu_string(&self) -> ~str {
+        self.to_ical_datetime()
+    }
+
+    /**
+     * Reads a compact Zulu time, exactly "YYYYMMDDTHHMMSSZ" or
+     * "YYYYMMDDTHHMMSS.mmmZ".
+     */
+    pub fn from_zulu_string(s: &str) -> Result<Date, ParseError> {
+        let digits_at = |start: uint, end: uint| {
+            s.slice(start, end).iter().all(|c| c >= '0' && c <= '9')
+        };
+        let ms = match s.len() {
+            16 if s[15] == 'Z' as u8 => 0,
+            20 if s[15] == '.' as u8 && s[19] == 'Z' as u8 && digits_at(16, 19) => {
+                from_str::<uint>(s.slice(16, 19)).unwrap()
+            }
+            _ => return Err(parse::InvalidFormat)
+        };
+        if !digits_at(0, 8) || s[8] != 'T' as u8 || !digits_at(9, 15) {
+            return Err(parse::InvalidFormat);
+        }
+
+        match parse::parse(s.slice_to(15), "%Y%m%dT%H%M%S") {
+            Ok(p) => match Date::from_parsed(&p) {
+                Ok(d) => Ok(Date::from_timestamp_millis(d.since_epoch + ms as i64)),
+                Err(e) => Err(e)
+            },
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+     * Returns the date as an RFC 3339 timestamp in UTC. Milliseconds are
+     * written only when there are some.
+     *
+     * utc:   "2009-02-13T23:31:30Z"
+     *        "2009-02-13T23:31:30.543Z"
+     */
+    pub fn rfc3339(&self) -> ~str {
+        if self.gcal.msec == 0 {
+            self.to_atom_date()
+        } else {
+            self.rfc3339_seconds() + format!(".{:03u}Z", self.gcal.msec)
+        }
+    }
+
+    /**
+     * Reads an RFC 3339 timestamp, "2009-02-13T23:31:30Z", with an optional
+     * fraction of up to nine digits, of which the first three are kept, and
+     * either "Z" or a "+HH:MM" / "-HH:MM" offset, which is applied.
+     *
+     * A missing or malformed offset gives InvalidFormat, and fields out of
+     * range, including offsets, give InvalidValue.
+     */
+    pub fn from_rfc3339(s: &str) -> Result<Date, ParseError> {
+        if s.len() < 20 {
+            return Err(parse::InvalidFormat);
+        }
+        let mut p = match parse::parse(s.slice_to(19), "%Y-%m-%dT%H:%M:%S") {
+            Ok(p) => p,
+            Err(e) => return Err(e)
+        };
+
+        let mut pos = 19;
+        let ms = match read_fraction_millis(s, &mut pos, 9) {
+            Some(ms) => ms,
+            None => return Err(parse::InvalidFormat)
+        };
+
+        let offset = s.slice_from(pos);
+        if offset != "Z" {
+            if offset.len() != 6 || offset[3] != ':' as u8 ||
+               (offset[0] != '+' as u8 && offset[0] != '-' as u8) {
+                return Err(parse::InvalidFormat);
+            }
+            p.offset = match parse::parse_timezone(offset) {
+                Ok(offset) => offset,
+                Err(e) => return Err(e)
+            };
+        }
+
+        match Date::from_parsed(&p) {
+            Ok(d) => Ok(Date::from_timestamp_millis(d.since_epoch + ms)),
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+     * Returns the date of a MongoDB "$date" value, in milliseconds since
+     * epoch.
+     */
+    pub fn from_mongodb_ms(ms: i64) -> Date {
+        Date::from_timestamp_millis(ms)
+    }
+
+    /**
+     * Reads a date of the MongoDB shell, an RFC 3339 timestamp wrapped as
+     * in ISODate("2009-02-13T23:31:30.543Z").
+     */
+    pub fn from_mongodb_isodate(s: &str) -> Result<Date, ParseError> {
+        if !s.starts_with("ISODate(\"") || !s.ends_with("\")") || s.len() < 11 {
+            return Err(parse::InvalidFormat);
+        }
+        Date::from_rfc3339(s.slice(9, s.len() - 2))
+    }
+
+    /**
+     * Returns the date in canonical MongoDB Extended JSON.
+     *
+     * utc:   { "$date": { "$numberLong": "1234567890543" } }
+     */
+    pub fn to_mongodb_extended_json(&self) -> ~str {
+        ~"{ \"$date\": { \"$numberLong\": \"" + self.since_epoch.to_str() + "\" } }"
+    }
+
+    /**
+     * Returns the date the way PostgreSQL writes a timestamp, with
+     * milliseconds.
+     *
+     * utc:   "2009-02-13 23:31:30.543"
+     */
+    pub fn to_pg_timestamp(&self) -> ~str {
+        self.strftime("%Y-%m-%d %H:%M:%S.") + format!("{:03u}", self.gcal.msec)
+    }
+
+    /**
+     * Reads a PostgreSQL timestamp, "2009-02-13 23:31:30", with up to six
+     * fraction digits, ".543210", of which the first three are kept. A
+     * timestamptz offset such as "+05:30" or "-08" may follow and is applied.
+     */
+    pub fn from_pg_timestamp(s: &str) -> Result<Date, ParseError> {
+        if s.len() < 19 {
+            return Err(parse::InvalidFormat);
+        }
+        let mut p = match parse::parse(s.slice_to(19), "%Y-%m-%d %H:%M:%S") {
+            Ok(p) => p,
+            Err(e) => return Err(e)
+        };
+
+        let mut pos = 19;
+        let ms = match read_fraction_millis(s, &mut pos, 6) {
+            Some(ms) => ms,
+            None => return Err(parse::InvalidFormat)
+        };
+
+        if pos < s.len() {
+            if s[pos] != '+' as u8 && s[pos] != '-' as u8 {
+                return Err(parse::InvalidFormat);
+            }
+            p.offset = match parse::parse_timezone(s.slice_from(pos)) {
+                Ok(offset) => offset,
+                Err(_) => return Err(parse::InvalidFormat)
+            };
+        }
+
+        match Date::from_parsed(&p) {
+            Ok(d) => Ok(Date::from_timestamp_millis(d.since_epoch + ms)),
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+     * Reads a timestamp in any of the common formats: ISO 8601 and RFC 3339,
+     * RFC 2822 and HTTP dates, SQL timestamps, ctime, "DD/MM/YYYY",
+     * "MM/DD/YYYY", "DD-Mon-YYYY", "Mon DD YYYY", "YYYYMMDD", "YYYY/MM/DD"
+     * and plain numbers since epoch, with the unit guessed as in
+     * from_epoch_auto.
+     *
+     * A date such as "01/02/2009", that is valid both day first and month
+     * first, gives AmbiguousFormat.
+     */
+    pub fn from_timestamp_string(s: &str) -> Result<Date, ParseError> {
+        match Date::from_slash_date(s) {
+            Some(res) => return res,
+            None => ()
+        }
+
+        let res = match parse::parse_first(s, &["%Y-%m-%dT%H:%M:%S%z",
+                                                "%Y-%m-%dT%H:%M:%S",
+                                                "%Y-%m-%d %H:%M:%S",
+                                                "%Y-%m-%d",
+                                                "%a, %d %b %Y %H:%M:%S %z",
+                                                "%a, %d %b %Y %H:%M:%S %Z",
+                                                "%d %b %Y %H:%M:%S %z",
+                                                "%a %b %e %H:%M:%S %Y",
+                                                "%d-%b-%Y",
+                                                "%b %d %Y",
+                                                "%Y%m%d",
+                                                "%Y/%m/%d"]) {
+            Ok(p) => Date::from_parsed(&p),
+            Err(e) => Err(e)
+        };
+        if res.is_ok() {
+            return res;
+        }
+
+        match from_str::<i64>(s) {
+            Some(n) => match Date::from_epoch_auto(n) {
+                Some(d) => Ok(d),
+                None => Err(parse::InvalidValue)
+            },
+            None => res
+        }
+    }
+
+    /*
+     * Reads "DD/MM/YYYY" or "MM/DD/YYYY", or returns None if the input is
+     * neither.
+     */
+    fn from_slash_date(s: &str) -> Option<Result<Date, ParseError>> {
+        let dmy = match parse::parse(s, "%d/%m/%Y") {
+            Ok(p) => Date::from_parsed(&p),
+            Err(e) => Err(e)
+        };
+        let mdy = match parse::parse(s, "%m/%d/%Y") {
+            Ok(p) => Date::from_parsed(&p),
+            Err(e) => Err(e)
+        };
+
+        match (dmy, mdy) {
+            (Ok(a), Ok(b)) => {
+                if a.since_epoch == b.since_epoch { Some(Ok(a)) }
+                else { Some(Err(parse::AmbiguousFormat)) }
+            }
+            (Ok(a), Err(_)) => Some(Ok(a)),
+            (Err(_), Ok(b)) => Some(Ok(b)),
+            (Err(parse::InvalidValue), Err(_)) => Some(Err(parse::InvalidValue)),
+            (Err(_), Err(_)) => None
+        }
+    }
+
+    /**
+     * Returns the date in the RFC 7231 IMF-fixdate form used by the HTTP
+     * Expires header.
+     *
+     * utc:   "Fri, 13 Feb 2009 23:31:30 GMT"
+     */
+    pub fn to_http_expiry_header(&self) -> ~str {
+        self.strftime("%a, %d %b %Y %T GMT")
+    }
+
+    /**
+     * Returns an Expires header value that is always in the past, the epoch,
+     * to mark a response as already expired.
+     */
+    pub fn http_expiry_past() -> ~str {
+        Date::unix_epoch().to_http_expiry_header()
+    }
+
+    /**
+     * Allocates a Date object on the day of the given date at the time of
+     * day read by parse::parse_time, such as "14:30" or "2:30 PM".
+     */
+    pub fn from_date_and_time_str(date: &Date, time_str: &str) -> Result<Date, ParseError> {
+        let (hour, min, sec, ms) = match parse::parse_time(time_str) {
+            Ok(t) => t,
+            Err(e) => return Err(e)
+        };
+        Ok(Date::from_calendar(date.gcal.year, date.gcal.month, date.gcal.mday,
+                               hour, min, sec, ms))
+    }
+
+    /**
+     * Reads a date relative to a base date, in the notation used by
+     * financial systems: an optional "T" or "D" standing for the base date,
+     * a signed count and a unit, "D" (days, the default), "W" (weeks), "M"
+     * (months), "Y" (years) or "BD" (business days, Monday to Friday).
+     *
+     * "T+2BD", "D-30", "+6M", "T"
+     *
+     * Months and years keep the day of the month when it exists and use
+     * the last day of the month otherwise.
+     */
+    pub fn parse_relative(expr: &str, base: &Date) -> Result<Date, ParseError> {
+        let mut rest = expr;
+        if rest.starts_with("T") || rest.starts_with("D") {
+            rest = rest.slice_from(1);
+        }
+        if rest.is_empty() {
+            return Ok(*base);
+        }
+
+        let sign = match rest[0] as char {
+            '+' => 1,
+            '-' => -1,
+            _ => return Err(parse::InvalidFormat)
+        };
+        rest = rest.slice_from(1);
+
+        let mut digits = 0;
+        while digits < rest.len() && rest[digits] >= '0' as u8 && rest[digits] <= '9' as u8 {
+            digits += 1;
+        }
+        if digits == 0 {
+            return Err(parse::InvalidFormat);
+        }
+        let count = match from_str::<int>(rest.slice_to(digits)) {
+            Some(n) => sign * n,
+            None => return Err(parse::InvalidValue)
+        };
+
+        let date = match rest.slice_from(digits) {
+            "" | "D" => base.plus_days(count),
+            "W" => base.plus_days(count * 7),
+            "M" => base.plus_months(count),
+            "Y" => base.plus_months(count * 12),
+            "BD" => base.plus_business_days(count),
+            _ => return Err(parse::InvalidFormat)
+        };
+        match date {
+            Some(d) => Ok(d),
+            None => Err(parse::InvalidValue)
+        }
+    }
+
+    /**
+     * Moves the date by a number of months, negative to go back, keeping the
+     * time of day. The day of the month is clamped to the length of the new
+     * month: January 31st plus one month is the last day of February. Fails
+     * if that goes before year 0.
+     */
+    pub fn add_months(&self, n: i32) -> Date {
+        match self.plus_months(n as int) {
+            Some(d) => d,
+            None => fail!(format!("adding {} months goes before year 0", n))
+        }
+    }
+
+    /**
+     * Moves the date by a number of years, negative to go back, keeping the
+     * month and the time of day. February 29th becomes the 28th in years
+     * that are not leap years. This is add_months of 12 months a year.
+     * Fails if that goes before year 0.
+     */
+    pub fn add_years(&self, n: i32) -> Date {
+        match self.plus_months(n as int * 12) {
+            Some(d) => d,
+            None => fail!(format!("adding {} years goes before year 0", n))
+        }
+    }
+
+    /*
+     * Moves the date by a number of days, or returns None if that goes
+     * before year 0.
+     */
+    fn plus_days(&self, days: int) -> Option<Date> {
+        Date::from_timestamp_millis_checked(self.since_epoch + days as i64 * 86400000)
+    }
+
+    /*
+     * Moves the date by a number of months, keeping the time of day. The day
+     * of the month is clamped to the length of the new month.
+     */
+    fn plus_months(&self, months: int) -> Option<Date> {
+        let total = (self.gcal.year * 12 + self.gcal.month - 1) as int + months;
+        if total < 0 {
+            return None;
+        }
+
+        let (year, month) = ((total / 12) as uint, (total % 12 + 1) as uint);
+        let size = GCalendar::days_in_month(year as u32, month as u32) as uint;
+        let day = if self.gcal.mday > size { size } else { self.gcal.mday };
+        Some(Date::from_calendar(year, month, day, self.gcal.hour, self.gcal.min,
+                                 self.gcal.sec, self.gcal.msec))
+    }
+
+    /*
+     * Moves the date by a number of days from Monday to Friday.
+     */
+    fn plus_business_days(&self, days: int) -> Option<Date> {
+        let step = if days < 0 { -1 } else { 1 };
+        let mut left = days.abs();
+        let mut moved = 0;
+        let mut wday = self.gcal.wday as int;
+
+        while left > 0 {
+            moved += step;
+            wday = (wday + step).mod_floor(&7);
+            if wday != 0 && wday != 6 {
+                left -= 1;
+            }
+        }
+        self.plus_days(moved)
+    }
+
+    /**
+     * Returns the date as seen from the given IANA timezone, such as
+     * "Asia/Kolkata". Only the standard time offset is applied, daylight
+     * saving time is not.
+     */
+    pub fn with_iana_tz(&self, tz_id: &str) -> Result<LocalDate, DateError> {
+        let offset = match iana_standard_offset(tz_id) {
+            Some(offset) => offset,
+            None => return Err(InvalidTimezone)
+        };
+
+        let local = self.since_epoch + offset as i64 * 60000;
+        Ok(LocalDate {
+            date: *self,
+            offset: offset,
+            zone: tz_id.to_owned(),
+            local: GCalendar::from_epoch_millis(local)
+        })
+    }
+
+    /**
+     * Returns the date as seen from the given timezone, such as a
+     * FixedOffset. Its offset is applied to the local calendar fields and
+     * its name is what %Z gives.
+     */
+    pub fn with_timezone<Tz: TimeZone>(&self, tz: &Tz) -> LocalDate {
+        let offset = tz.offset_seconds() as int / 60;
+        let local = self.since_epoch + offset as i64 * 60000;
+        LocalDate {
+            date: *self,
+            offset: offset,
+            zone: tz.name(),
+            local: GCalendar::from_epoch_millis(local)
+        }
+    }
+
+    /**
+     * Returns true if the two dates are at most the given number of days
+     * apart, in either direction.
+     */
+    pub fn within_days(&self, other: &Date, days: uint) -> bool {
+        self.millis_between(other) <= days * 86400000
+    }
+
+    /**
+     * Returns true if the two dates are at most the given number of hours
+     * apart, in either direction.
+     */
+    pub fn within_hours(&self, other: &Date, hours: uint) -> bool {
+        self.millis_between(other) <= hours * 3600000
+    }
+
+    /**
+     * Returns true if the two dates are at most the given number of minutes
+     * apart, in either direction.
+     */
+    pub fn within_minutes(&self, other: &Date, minutes: uint) -> bool {
+        self.millis_between(other) <= minutes * 60000
+    }
+
+    /**
+     * Returns true if the two dates are at most the given number of seconds
+     * apart, in either direction.
+     */
+    pub fn within_seconds(&self, other: &Date, seconds: uint) -> bool {
+        self.millis_between(other) <= seconds * 1000
+    }
+
+    fn millis_between(&self, other: &Date) -> uint {
+        (self.since_epoch - other.since_epoch).abs() as uint
+    }
+
+    /**
+     * Returns true if this date falls inside any of the given periods.
+     */
+    pub fn overlaps_any(&self, periods: &[Period]) -> bool {
+        periods.iter().any(|p| p.contains(self))
+    }
+
+    /**
+     * Returns the periods, out of the given ones, that contain this date.
+     */
+    pub fn find_overlapping_periods<'a>(&self, periods: &'a [Period])
+                                        -> ~[&'a Period] {
+        periods.iter().filter(|p| p.contains(self)).collect()
+    }
+
+    /**
+     * Returns the signed duration from the given date to this one, negative
+     * if this date is earlier. The operator form, date - date, can not be
+     * overloaded next to date - duration.
+     */
+    pub fn duration_since(&self, earlier: &Date) -> Duration {
+        Duration::from_millis(self.since_epoch - earlier.since_epoch)
+    }
+
+    /**
+     * Returns the dates from this one (included) to end (excluded), one day
+     * apart. DateRange::step_by takes another step.
+     */
+    pub fn until(&self, end: Date) -> DateRange {
+        DateRange::new(*self, end, Duration::from_days(1))
+    }
+}
+
+/**
+ * The ISO 8601 form of the date, as in iso_format.
+ */
+impl ToStr for Date {
+    fn to_str(&self) -> ~str {
+        self.iso_format()
+    }
+}
+
+/**
+ * Dates serialize as their ISO 8601 form, "2009-02-13 23:31:30", as given
+ * by iso_format. Milliseconds are not kept. Decoding fails on strings of
+ * another form.
+ */
+impl<S: Encoder> Encodable<S> for Date {
+    fn encode(&self, s: &mut S) {
+        s.emit_str(self.iso_format());
+    }
+}
+
+impl<D: Decoder> Decodable<D> for Date {
+    fn decode(d: &mut D) -> Date {
+        let s = d.read_str();
+        match Date::strptime(s, "%Y-%m-%d %H:%M:%S") {
+            Ok(date) => date,
+            Err(e) => fail!(format!("can't decode date {}: {}", s, e.to_str()))
+        }
+    }
+}
+
+impl Add<Duration, Date> for Date {
+    /**
+     * Fails if the sum is before year 0, see Date::checked_add.
+     */
+    fn add(&self, duration: &Duration) -> Date {
+        Date::from_timestamp_millis(self.since_epoch + duration.as_millis())
+    }
+}
+
+impl Sub<Duration, Date> for Date {
+    /**
+     * Fails if the difference is before year 0, see Date::checked_sub.
+     */
+    fn sub(&self, duration: &Duration) -> Date {
+        Date::from_timestamp_millis(self.since_epoch - duration.as_millis())
+    }
+}
+
+impl LocalDate {
+    pub fn get_date(&self) -> Date {
+        self.date
+    }
+
+    /**
+    * Returns the offset of the timezone, in minutes east of UTC.
+    */
+    pub fn get_offset(&self) -> int {
+        self.offset
+    }
+
+    /**
+    * Returns the Calendar object in local time.
+    */
+    pub fn get_cal(&self) -> GCalendar {
+        self.local
+    }
+
+    /**
+    * Returns the name of the timezone.
+    */
+    pub fn get_zone(&self) -> ~str {
+        self.zone.clone()
+    }
+
+    /**
+     * Formats the local time like Date::strftime. %z gives the offset of
+     * the timezone and %Z its name.
+     */
+    pub fn strftime(&self, format: &str) -> ~str {
+        let mut buf = ~"";
+
+        for item in CompiledFormat::new(format).get_items().iter() {
+            match *item {
+                Literal(ref text) => buf.push_str(text.as_slice()),
+                Specifier('z') => {
+                    let sign = if self.offset < 0 { '-' } else { '+' };
+                    let offset = self.offset.abs();
+                    buf.push_str(format!("{}{:02d}{:02d}", sign, offset / 60, offset % 60));
+                }
+                Specifier('Z') => buf.push_str(self.zone.as_slice()),
+                Specifier(ch) => buf.push_str(self.local.get_date(ch))
+            }
+        }
+
+        buf
+    }
+}
+
+/**
+ * A Date that is hashed and compared by its calendar day only, ignoring the
+ * time of day. Useful as a key to group dates by day.
+ */
+pub struct DateOnly(Date);
+
+/**
+ * A Date that is hashed and compared by its time of day only, ignoring the
+ * calendar day.
+ */
+pub struct TimeOnly(Date);
+
+impl DateOnly {
+    /* Days since epoch */
+    fn key(&self) -> i64 {
+        let DateOnly(ref d) = *self;
+        d.since_epoch.div_floor(&86400000)
+    }
+}
+
+impl TimeOnly {
+    /* Milliseconds since midnight */
+    fn key(&self) -> i64 {
+        let TimeOnly(ref d) = *self;
+        d.since_epoch.mod_floor(&86400000)
+    }
+}
+
+impl Eq for DateOnly {
+    fn eq(&self, other: &DateOnly) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl TotalEq for DateOnly {
+    fn equals(&self, other: &DateOnly) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Ord for DateOnly {
+    fn lt(&self, other: &DateOnly) -> bool {
+        self.key() < other.key()
+    }
+}
+
+impl TotalOrd for DateOnly {
+    fn cmp(&self, other: &DateOnly) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl IterBytes for DateOnly {
+    fn iter_bytes(&self, lsb0: bool, f: to_bytes::Cb) -> bool {
+        self.key().iter_bytes(lsb0, f)
+    }
+}
+
+impl Eq for TimeOnly {
+    fn eq(&self, other: &TimeOnly) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl TotalEq for TimeOnly {
+    fn equals(&self, other: &TimeOnly) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Ord for TimeOnly {
+    fn lt(&self, other: &TimeOnly) -> bool {
+        self.key() < other.key()
+    }
+}
+
+impl TotalOrd for TimeOnly {
+    fn cmp(&self, other: &TimeOnly) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl IterBytes for TimeOnly {
+    fn iter_bytes(&self, lsb0: bool, f: to_bytes::Cb) -> bool {
+        self.key().iter_bytes(lsb0, f)
+    }
+}
+
+/**
+ * A Date that serializes as its milliseconds since epoch, 1234567890543,
+ * keeping the milliseconds that the ISO 8601 form of Date drops.
+ */
+pub struct EpochMillis(Date);
+
+impl<S: Encoder> Encodable<S> for EpochMillis {
+    fn encode(&self, s: &mut S) {
+        let EpochMillis(ref d) = *self;
+        s.emit_i64(d.since_epoch);
+    }
+}
+
+impl<D: Decoder> Decodable<D> for EpochMillis {
+    fn decode(d: &mut D) -> EpochMillis {
+        EpochMillis(Date::from_timestamp_millis(d.read_i64()))
+    }
+}
+
+/*
+ * Milliseconds in a NetCDF time unit.
+ */
+fn netcdf_unit_millis(unit: &str) -> Option<i64> {
+    match unit {
+        "seconds" => Some(1000),
+        "hours" => Some(3600000),
+        "days" => Some(86400000),
+        _ => None
+    }
+}
+
+/*
+ * Reads an optional fraction of a second, a '.' and up to max_digits digits,
+ * at pos and returns it in milliseconds, dropping the digits past the third.
+ * Gives None when the '.' has no digits or too many.
+ */
+fn read_fraction_millis(s: &str, pos: &mut uint, max_digits: uint) -> Option<i64> {
+    if *pos >= s.len() || s[*pos] != '.' as u8 {
+        return Some(0);
+    }
+    *pos += 1;
+    let start = *pos;
+    let mut ms = 0;
+    while *pos < s.len() && s[*pos] >= '0' as u8 && s[*pos] <= '9' as u8 {
+        if *pos - start < 3 {
+            ms = ms * 10 + (s[*pos] - '0' as u8) as i64;
+        }
+        *pos += 1;
+    }
+    if *pos == start || *pos - start > max_digits {
+        return None;
+    }
+    for _ in range(*pos - start, 3) {
+        ms *= 10;
+    }
+    Some(ms)
+}
+
+fn millis_to_timespec(ms: i64) -> Timespec {
+    Timespec::new(ms.div_floor(&1000), (ms.mod_floor(&1000) * 1000000) as i32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Date, Seconds, Millis, Micros, Nanos};
+    use super::{DateOnly, TimeOnly, EpochMillis};
+    use extra::json;
+    use extra::serialize::{Encodable, Decodable};
+    use bahai::BahaiDate;
+    use types::{Monday, Sunday};
+    use chinese::{Jia, Geng, Zi, Chou};
+    use std::hashmap::HashMap;
+    use std::io;
+    use std::i64;
+    use period::Period;
+    use format;
+    use format::UnexpectedSpecifier;
+    use extra::time::Timespec;
+    use parse;
+    use parse::{InvalidFormat, InvalidValue, UnknownTimezone, AmbiguousFormat};
+    use parse::{UnknownSpecifier, UnexpectedInput};
+    use gcalendar::{InvalidYear, InvalidMonth, InvalidDay, InvalidHour};
+    use gcalendar::{InvalidMinute, InvalidSecond, InvalidMillisecond, InvalidTimezone};
+    use gcalendar::InvalidNanosecond;
+    use gcalendar::random_epochs;
+    use duration::Duration;
+    use timezone::FixedOffset;
+
+    #[test]
+    fn from_timestamp_millis() {
+        let d = Date::from_timestamp_millis(433166421023);
+        assert_eq!(d.get_time(), 433166421023);
+    }
+
+    #[test]
+    fn now() {
+        println("now: " + Date::now_strftime("%Y-%m-%d %H:%M:%S"));
+        assert_eq!(true, true);
+    }
+
+    #[test]
+    fn test_strftime() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.strftime(""), ~"");
+        assert_eq!(d.strftime("%A"), ~"Friday");
+        assert_eq!(d.strftime("%a"), ~"Fri");
+        assert_eq!(d.strftime("%B"), ~"February");
+        assert_eq!(d.strftime("%b"), ~"Feb");
+        assert_eq!(d.strftime("%C"), ~"20");
+        assert_eq!(d.strftime("%c"), ~"Fri Feb 13 23:31:30 2009");
+        assert_eq!(d.strftime("%D"), ~"02/13/09");
+        assert_eq!(d.strftime("%d"), ~"13");
+        assert_eq!(d.strftime("%e"), ~"13");
+        assert_eq!(d.strftime("%f"), ~"543000000");
+        assert_eq!(Date::from_timestamp_millis(1000).strftime("%f"), ~"000000000");
+        assert_eq!(Date::from_timestamp_millis(1500).strftime("%f"), ~"500000000");
+        assert_eq!(d.strftime("%F"), ~"2009-02-13");
+        assert_eq!(d.strftime("%G"), ~"2009");
+        assert_eq!(d.strftime("%g"), ~"09");
+        assert_eq!(d.strftime("%H"), ~"23");
+        assert_eq!(d.strftime("%I"), ~"11");
+        assert_eq!(d.strftime("%j"), ~"044");
+        assert_eq!(d.strftime("%k"), ~"23");
+        assert_eq!(d.strftime("%l"), ~"11");
+        assert_eq!(d.strftime("%M"), ~"31");
+        assert_eq!(d.strftime("%m"), ~"02");
+        assert_eq!(d.strftime("%n"), ~"\n");
+        assert_eq!(d.strftime("%P"), ~"pm");
+        assert_eq!(d.strftime("%p"), ~"PM");
+        assert_eq!(d.strftime("%R"), ~"23:31");
+        assert_eq!(d.strftime("%r"), ~"11:31:30 PM");
+        assert_eq!(d.strftime("%S"), ~"30");
+        assert_eq!(d.strftime("%s"), ~"1234567890");
+        assert_eq!(d.strftime("%T"), ~"23:31:30");
+        assert_eq!(d.strftime("%t"), ~"\t");
+        assert_eq!(d.strftime("%U"), ~"06");
+        assert_eq!(d.strftime("%u"), ~"5");
+        assert_eq!(d.strftime("%V"), ~"07");
+        assert_eq!(d.strftime("%v"), ~"13-Feb-2009");
+        assert_eq!(d.strftime("%W"), ~"06");
+        assert_eq!(d.strftime("%w"), ~"5");
+        assert_eq!(d.strftime("%Y"), ~"2009");
+        assert_eq!(d.strftime("%y"), ~"09");
+        assert_eq!(d.strftime("%z"), ~"+0000");
+        assert_eq!(d.strftime("%%"), ~"%");
+
+        assert_eq!(d.iso_format(), ~"2009-02-13 23:31:30");
+        assert_eq!(d.ctime(), ~"Fri Feb 13 23:31:30 2009");
+        assert_eq!(d.rfc822z(), ~"Fri, 13 Feb 2009 23:31:30 +0000");
+        assert_eq!(d.rfc2822(), ~"Fri, 13 Feb 2009 23:31:30 +0000");
+        assert_eq!(Date::from_feed_date(d.rfc2822()).unwrap().get_time(), 1234567890000);
+        let d = Date::from_ymd_hms(2012, 3, 2, 4, 5, 6).unwrap();
+        assert_eq!(d.rfc2822(), ~"Fri, 02 Mar 2012 04:05:06 +0000");
+    }
+
+    #[test]
+    fn overlaps_any() {
+        let period = |start: i64, end: i64| {
+            Period::new(Date::from_timestamp_millis(start),
+                        Date::from_timestamp_millis(end))
+        };
+        let periods = ~[period(1000, 2000), period(1500, 3000), period(5000, 6000)];
+        let d = Date::from_timestamp_millis(1800);
+        assert!(d.overlaps_any(periods));
+        assert_eq!(d.find_overlapping_periods(periods).len(), 2);
+
+        let d = Date::from_timestamp_millis(4000);
+        assert!(!d.overlaps_any(periods));
+        assert_eq!(d.find_overlapping_periods(periods).len(), 0);
+    }
+
+    #[test]
+    fn epochs() {
+        assert_eq!(Date::unix_epoch().get_time(), 0);
+        assert_eq!(Date::unix_epoch().iso_format(), ~"1970-01-01 00:00:00");
+        assert_eq!(Date::gps_epoch().iso_format(), ~"1980-01-06 00:00:00");
+        assert_eq!(Date::j2000().iso_format(), ~"2000-01-01 12:00:00");
+        assert_eq!(Date::ntp_epoch().get_time(), -2208988800000);
+        assert_eq!(Date::ntp_epoch().iso_format(), ~"1900-01-01 00:00:00");
+    }
+
+    #[test]
+    fn from_str_with_tz() {
+        let utc = Date::from_str_with_tz("2009-02-13 23:31:30", "UTC").unwrap();
+        assert_eq!(utc.get_time(), 1234567890000);
+
+        let ist = Date::from_str_with_tz("2009-02-14 05:01:30", "+05:30").unwrap();
+        assert_eq!(ist.get_time(), utc.get_time());
+        let ist = Date::from_str_with_tz("2009-02-14 05:01:30", "Asia/Kolkata").unwrap();
+        assert_eq!(ist.get_time(), utc.get_time());
+
+        let est = Date::from_str_with_tz("2009-02-13 18:31:30", "-05:00").unwrap();
+        assert_eq!(est.get_time(), utc.get_time());
+        let est = Date::from_str_with_tz("2009-02-13 18:31:30", "America/New_York").unwrap();
+        assert_eq!(est.get_time(), utc.get_time());
+
+        let err = Date::from_str_with_tz("2009-02-13 23:31:30", "Mars/Olympus_Mons");
+        assert_eq!(err.unwrap_err(), UnknownTimezone);
+        let err = Date::from_str_with_tz("2009-02-30 23:31:30", "UTC");
+        assert_eq!(err.unwrap_err(), InvalidValue);
+    }
+
+    #[test]
+    fn month_and_weekday() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.month_number(), 2);
+        assert_eq!(d.month_name(), "February");
+        assert_eq!(d.month_name_abbrev(), "Feb");
+        assert_eq!(d.weekday_number(), 5);
+        assert_eq!(d.weekday_name(), "Friday");
+        assert_eq!(d.weekday_name_abbrev(), "Fri");
+
+        let d = Date::from_timestamp_millis(0);
+        assert_eq!(d.month_name(), "January");
+        assert_eq!(d.weekday_name(), "Thursday");
+    }
+
+    #[test]
+    fn timespec() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.to_timespec(), Timespec::new(1234567890, 543000000));
+        assert_eq!(Date::from_timespec(Timespec::new(1234567890, 543999999)).get_time(),
+                   1234567890543);
+
+        let earlier = Date::from_timestamp_millis(1234567800000);
+        assert_eq!(d.elapsed_since(&earlier), Some(Timespec::new(90, 543000000)));
+        assert_eq!(d.elapsed_since(&d), Some(Timespec::new(0, 0)));
+        assert_eq!(earlier.elapsed_since(&d), None);
+    }
+
+    #[test]
+    fn timespec_round_trip() {
+        let ts = Timespec::new(1234567890, 543000000);
+        let d = Date::from_timespec(ts);
+        assert_eq!(d.get_cal().all_components(), (2009, 2, 13, 23, 31, 30, 543, 5, 43));
+        assert_eq!(d.to_timespec(), ts);
+        assert_eq!(Date::from_timespec(d.to_timespec()), d);
+
+        let ts = Timespec::new(-1, 500000000);
+        let d = Date::from_timespec(ts);
+        assert_eq!(d.get_time(), -500);
+        assert_eq!(d.strftime("%F %T"), ~"1969-12-31 23:59:59");
+        assert_eq!(d.to_timespec(), ts);
+
+        let d = Date::from_proleptic_gregorian(1900, 1, 1, 0, 0, 0, 1).unwrap();
+        assert_eq!(d.to_timespec(), Timespec::new(-2208988800, 1000000));
+        assert_eq!(Date::from_timespec(d.to_timespec()), d);
+    }
+
+    #[test]
+    fn feed_dates() {
+        let d = Date::from_timestamp_millis(1234567890000);
+        assert_eq!(d.to_atom_date(), ~"2009-02-13T23:31:30Z");
+        assert_eq!(d.to_rss_date(), ~"Fri, 13 Feb 2009 23:31:30 +0000");
+
+        let d543 = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d543.to_atom_updated_element(), ~"<updated>2009-02-13T23:31:30Z</updated>");
+        assert_eq!(d543.to_rss_pubdate_element(),
+                   ~"<pubDate>Fri, 13 Feb 2009 23:31:30 +0000</pubDate>");
+
+        assert_eq!(Date::from_feed_date(d.to_atom_date()).unwrap().get_time(), d.get_time());
+        assert_eq!(Date::from_feed_date(d.to_rss_date()).unwrap().get_time(), d.get_time());
+        assert_eq!(Date::from_feed_date("2009-02-13T23:31:30.543Z").unwrap(), d543);
+        assert_eq!(Date::from_feed_date(d543.rfc3339()).unwrap(), d543);
+        assert_eq!(Date::from_feed_date("2009-02-14T05:01:30.543+05:30").unwrap(), d543);
+
+        let other = ["2009-02-14T05:01:30+05:30", "Fri, 13 Feb 2009 18:31:30 EST",
+                     "Fri, 13 Feb 2009 23:31:30 GMT", "13 Feb 2009 23:31:30 -0000"];
+        for s in other.iter() {
+            assert_eq!(Date::from_feed_date(*s).unwrap().get_time(), d.get_time());
+        }
+        assert!(Date::from_feed_date("13/02/2009").is_err());
+    }
+
+    #[test]
+    fn strftime_n() {
+        let dates: ~[Date] = random_epochs(100).iter()
+            .map(|&e| Date::from_timestamp_millis(e)).collect();
+
+        let format = "%A %c %D %e %F %G %g %I %j %l %p %r %s %U %u %V %W %y %Z %%";
+        let formatted = Date::strftime_n(dates, format);
+        assert_eq!(formatted.len(), 100);
+        for (d, s) in dates.iter().zip(formatted.iter()) {
+            assert_eq!(d.strftime(format), s.clone());
+        }
+        assert_eq!(Date::strftime_n([], format).len(), 0);
+    }
+
+    #[test]
+    fn from_proleptic_gregorian() {
+        let d = Date::from_proleptic_gregorian(2009, 2, 13, 23, 31, 30, 543).unwrap();
+        assert_eq!(d.get_time(), 1234567890543);
+        assert_eq!(d.get_cal().get_day_of_week(), 5);
+        assert_eq!(d.get_cal().get_day_of_year(), 43);
+
+        let d = Date::from_proleptic_gregorian(2008, 12, 31, 0, 0, 0, 0).unwrap();
+        assert_eq!(d.iso_format(), ~"2008-12-31 00:00:00");
+
+        let check = |year, month, day, hour, min, sec, ms| {
+            Date::from_proleptic_gregorian(year, month, day, hour, min, sec, ms).unwrap_err()
+        };
+        let d = Date::from_proleptic_gregorian(1969, 12, 31, 23, 59, 59, 0).unwrap();
+        assert_eq!(d.get_time(), -1000);
+        let d = Date::from_proleptic_gregorian(1900, 1, 1, 0, 0, 0, 0).unwrap();
+        assert_eq!(d.get_time(), -2208988800000);
+        assert_eq!(d.weekday_name(), "Monday");
+
+        assert_eq!(check(2009, 13, 1, 0, 0, 0, 0), InvalidMonth);
+        assert_eq!(check(2009, 2, 29, 0, 0, 0, 0), InvalidDay);
+        assert_eq!(check(2009, 2, 0, 0, 0, 0, 0), InvalidDay);
+        assert_eq!(check(2009, 2, 13, 24, 0, 0, 0), InvalidHour);
+        assert_eq!(check(2009, 2, 13, 23, 60, 0, 0), InvalidMinute);
+        assert_eq!(check(2009, 2, 13, 23, 59, 61, 0), InvalidSecond);
+        assert_eq!(check(2009, 2, 13, 23, 59, 59, 1000), InvalidMillisecond);
+    }
+
+    #[test]
+    fn decade_and_millennium() {
+        let d = Date::from_proleptic_gregorian(2009, 2, 13, 0, 0, 0, 0).unwrap();
+        assert_eq!(d.decade(), 200);
+        assert_eq!(d.millennium(), 3);
+        assert!(!d.is_century_year());
+        assert!(!d.is_millennium_year());
+        assert_eq!(d.years_since_century_start(), 9);
+
+        let d = Date::from_proleptic_gregorian(2000, 1, 1, 0, 0, 0, 0).unwrap();
+        assert_eq!(d.decade(), 200);
+        assert_eq!(d.millennium(), 2);
+        assert!(d.is_century_year());
+        assert!(d.is_millennium_year());
+        assert_eq!(d.years_since_century_start(), 0);
+
+        let d = Date::from_proleptic_gregorian(1999, 12, 31, 0, 0, 0, 0).unwrap();
+        assert_eq!(d.decade(), 199);
+        assert_eq!(d.millennium(), 2);
+
+        let d = Date::from_proleptic_gregorian(2100, 6, 1, 0, 0, 0, 0).unwrap();
+        assert!(d.is_century_year());
+        assert!(!d.is_millennium_year());
+    }
+
+    #[test]
+    fn within() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        let later = Date::from_timestamp_millis(1234567890543 + 30 * 86400000);
+
+        assert!(d.within_days(&later, 30));
+        assert!(later.within_days(&d, 30));
+        assert!(!d.within_days(&later, 29));
+        assert!(d.within_days(&d, 0));
+
+        assert!(d.within_hours(&later, 720));
+        assert!(!d.within_hours(&later, 719));
+        assert!(d.within_minutes(&later, 43200));
+        assert!(!d.within_minutes(&later, 43199));
+        assert!(d.within_seconds(&later, 2592000));
+        assert!(!d.within_seconds(&later, 2591999));
+    }
+
+    #[test]
+    fn iso_week_date() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.format_iso_week_date(), ~"2009-W07-5");
+        assert_eq!(Date::parse_iso_week_date("2009-W07-5").unwrap().iso_format(),
+                   ~"2009-02-13 00:00:00");
+
+        /* Weeks spilling over into the neighbouring years */
+        assert_eq!(Date::parse_iso_week_date("2009-W01-1").unwrap().iso_format(),
+                   ~"2008-12-29 00:00:00");
+        assert_eq!(Date::parse_iso_week_date("2004-W53-7").unwrap().iso_format(),
+                   ~"2005-01-02 00:00:00");
+        assert_eq!(Date::parse_iso_week_date("2009-W53-1").unwrap().iso_format(),
+                   ~"2009-12-28 00:00:00");
+
+        for s in ["2009-W07-5", "2009-W01-1", "2004-W53-7", "2015-W53-4"].iter() {
+            let parsed = Date::parse_iso_week_date(*s).unwrap();
+            assert_eq!(parsed.format_iso_week_date(), s.to_owned());
+        }
+
+        assert_eq!(Date::parse_iso_week_date("2010-W53-1").unwrap_err(), InvalidValue);
+        assert_eq!(Date::parse_iso_week_date("2009-W00-1").unwrap_err(), InvalidValue);
+        assert_eq!(Date::parse_iso_week_date("2009-W07-8").unwrap_err(), InvalidValue);
+        assert!(Date::parse_iso_week_date("2009-07-5").is_err());
+    }
+
+    #[test]
+    fn http_expiry_header() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.to_http_expiry_header(), ~"Fri, 13 Feb 2009 23:31:30 GMT");
+        assert_eq!(Date::http_expiry_past(), ~"Thu, 01 Jan 1970 00:00:00 GMT");
+
+        let d = Date::from_proleptic_gregorian(2024, 3, 5, 7, 8, 9, 0).unwrap();
+        let header = d.to_http_expiry_header();
+        assert_eq!(header, ~"Tue, 05 Mar 2024 07:08:09 GMT");
+        assert_eq!(header.len(), 29);
+        let p = parse::parse(header, "%a, %d %b %Y %H:%M:%S GMT").unwrap();
+        assert_eq!((p.year, p.month, p.mday, p.hour, p.min, p.sec), (2024, 3, 5, 7, 8, 9));
+    }
+
+    #[test]
+    fn days_since_epoch() {
+        let epoch = Date::unix_epoch();
+        assert_eq!(epoch.days_since_epoch(&epoch), 0);
+        let d = Date::from_proleptic_gregorian(1970, 1, 2, 0, 0, 0, 0).unwrap();
+        assert_eq!(d.days_since_epoch(&epoch), 1);
+        assert_eq!(epoch.days_since_epoch(&d), -1);
+
+        let d = Date::from_timestamp_millis(43200000);
+        assert_eq!(d.days_since_epoch(&epoch), 0);
+        assert_eq!(epoch.days_since_epoch(&d), -1);
+        assert_eq!(epoch.millis_since_epoch(&d), -43200000);
+
+        assert_eq!(Date::j2000().days_since_epoch(&Date::gps_epoch()), 7300);
+        assert_eq!(Date::j2000().millis_since_epoch(&Date::gps_epoch()),
+                   7300 * 86400000 + 43200000);
+    }
+
+    #[test]
+    fn from_epoch_with_unit() {
+        let ms = 1234567890543;
+        let time = |value: i64, unit| Date::from_epoch_with_unit(value, unit).unwrap().get_time();
+        assert_eq!(time(1234567890, Seconds), 1234567890000);
+        assert_eq!(time(ms, Millis), ms);
+        assert_eq!(time(ms * 1000 + 999, Micros), ms);
+        assert_eq!(time(ms * 1000000 + 999999, Nanos), ms);
+
+        assert_eq!(time(-1234567890, Seconds), -1234567890000);
+        assert_eq!(time(-ms, Millis), -ms);
+        assert_eq!(time(-1, Micros), -1);
+        assert_eq!(time(-1, Nanos), -1);
+
+        assert_eq!(Date::from_epoch_with_unit(i64::max_value / 100, Seconds), None);
+        assert_eq!(Date::from_epoch_with_unit(-62167219201, Seconds), None);
+    }
+
+    #[test]
+    fn from_epoch_auto() {
+        let ms = 1234567890543;
+        let time = |value: i64| Date::from_epoch_auto(value).unwrap().get_time();
+        assert_eq!(time(1234567890), 1234567890000);
+        assert_eq!(time(ms), ms);
+        assert_eq!(time(ms * 1000), ms);
+        assert_eq!(time(ms * 1000000), ms);
+        assert_eq!(time(0), 0);
+        /* Largest seconds value, late 2286 */
+        assert_eq!(Date::from_epoch_auto(9999999999).unwrap().get_cal().get_year(), 2286);
+
+        /* November 1930 in each unit */
+        assert_eq!(time(-1234567890), -1234567890000);
+        assert_eq!(time(-1234567890000), -1234567890000);
+        assert_eq!(time(-1234567890000000), -1234567890000);
+        assert_eq!(time(-1234567890000000000), -1234567890000);
+        assert_eq!(Date::from_epoch_auto(-9999999999).unwrap().get_cal().get_year(), 1653);
+    }
+
+    #[test]
+    fn netcdf_string() {
+        let epoch = Date::unix_epoch();
+        let d = Date::from_timestamp_millis(1234567890543);
+        let s = d.to_netcdf_string("hours", &epoch).unwrap();
+        assert_eq!(s, ~"342935 hours since 1970-01-01 00:00:00");
+        assert_eq!(Date::from_netcdf_string(s).unwrap().get_time(), 342935 * 3600000);
+
+        let s = d.to_netcdf_string("seconds", &Date::j2000()).unwrap();
+        assert_eq!(s, ~"287839890 seconds since 2000-01-01 12:00:00");
+        assert_eq!(Date::from_netcdf_string(s).unwrap().get_time(), 1234567890000);
+
+        let reference = Date::from_proleptic_gregorian(2009, 3, 1, 0, 0, 0, 0).unwrap();
+        let s = d.to_netcdf_string("days", &reference).unwrap();
+        assert_eq!(s, ~"-16 days since 2009-03-01 00:00:00");
+        let back = Date::from_netcdf_string(s).unwrap();
+        assert_eq!(back.strftime("%Y-%m-%d %H:%M:%S"), ~"2009-02-13 00:00:00");
+        assert_eq!(d.to_netcdf_string("weeks", &reference), None);
+
+        assert_eq!(Date::from_netcdf_string("3 weeks since 2009-03-01 00:00:00").unwrap_err(),
+                   InvalidValue);
+        assert_eq!(Date::from_netcdf_string("3 days after 2009-03-01 00:00:00").unwrap_err(),
+                   InvalidFormat);
+        assert_eq!(Date::from_netcdf_string("many days since 2009-03-01 00:00:00").unwrap_err(),
+                   InvalidFormat);
+        let d = Date::from_netcdf_string("-1 days since 1970-01-01 00:00:00").unwrap();
+        assert_eq!(d.strftime("%F %T"), ~"1969-12-31 00:00:00");
+    }
+
+    #[test]
+    fn date_only() {
+        let morning = Date::from_proleptic_gregorian(2009, 2, 13, 8, 0, 0, 0).unwrap();
+        let night = Date::from_proleptic_gregorian(2009, 2, 13, 23, 31, 30, 0).unwrap();
+        let next = Date::from_proleptic_gregorian(2009, 2, 14, 8, 0, 0, 0).unwrap();
+
+        assert!(DateOnly(morning) == DateOnly(night));
+        assert!(DateOnly(morning) != DateOnly(next));
+        assert!(DateOnly(night) < DateOnly(next));
+        assert_eq!(DateOnly(night).cmp(&DateOnly(morning)), Equal);
+
+        let mut by_day = HashMap::new();
+        for d in [morning, night, next].iter() {
+            by_day.insert_or_update_with(DateOnly(*d), 1u, |_, n| *n += 1);
+        }
+        assert_eq!(by_day.len(), 2);
+        assert_eq!(*by_day.get(&DateOnly(morning)), 2);
+    }
+
+    #[test]
+    fn time_only() {
+        let morning = Date::from_proleptic_gregorian(2009, 2, 13, 8, 0, 0, 0).unwrap();
+        let night = Date::from_proleptic_gregorian(2009, 2, 13, 23, 31, 30, 0).unwrap();
+        let next = Date::from_proleptic_gregorian(2009, 2, 14, 8, 0, 0, 0).unwrap();
+
+        assert!(TimeOnly(morning) == TimeOnly(next));
+        assert!(TimeOnly(morning) != TimeOnly(night));
+        assert!(TimeOnly(next) < TimeOnly(night));
+        assert_eq!(TimeOnly(next).cmp(&TimeOnly(morning)), Equal);
+    }
+
+    #[test]
+    fn ical_datetime() {
+        let d = Date::from_timestamp_millis(1234567890000);
+        assert_eq!(d.to_ical_datetime(), ~"20090213T233130Z");
+
+        let utc = Date::from_ical_datetime("20090213T233130Z", None).unwrap();
+        assert_eq!(utc.get_time(), d.get_time());
+        let utc = Date::from_ical_datetime("20090213T233130Z", Some("Asia/Tokyo")).unwrap();
+        assert_eq!(utc.get_time(), d.get_time());
+
+        let floating = Date::from_ical_datetime("20090213T233130", None).unwrap();
+        assert_eq!(floating.get_time(), d.get_time());
+        let est = Date::from_ical_datetime("20090213T183130", Some("-05:00")).unwrap();
+        assert_eq!(est.get_time(), d.get_time());
+
+        let est = Date::from_ical_datetime("TZID=America/New_York:20090213T183130",
+                                           None).unwrap();
+        assert_eq!(est.get_time(), d.get_time());
+        let est = Date::from_ical_datetime("TZID=America/New_York:20090213T183130",
+                                           Some("UTC")).unwrap();
+        assert_eq!(est.get_time(), d.get_time());
+
+        let err = Date::from_ical_datetime("TZID=Mars/Olympus_Mons:20090213T183130", None);
+        assert_eq!(err.unwrap_err(), UnknownTimezone);
+        assert_eq!(Date::from_ical_datetime("2009-02-13T23:31:30Z", None).unwrap_err(),
+                   InvalidFormat);
+        assert_eq!(Date::from_ical_datetime("TZID=UTC", None).unwrap_err(), InvalidFormat);
+    }
+
+    #[test]
+    fn bahai() {
+        let d = Date::from_proleptic_gregorian(2024, 11, 12, 10, 0, 0, 0).unwrap();
+        let bd = d.to_bahai().unwrap();
+        assert_eq!((bd.get_year(), bd.get_month(), bd.get_day()), (181, 13, 9));
+        assert_eq!(bd.month_name(), "Qudrat");
+
+        let back = Date::from_bahai(bd).unwrap();
+        assert_eq!(back.strftime("%Y-%m-%d %H:%M:%S"), ~"2024-11-12 00:00:00");
+
+        let naw_ruz = BahaiDate::new(1, 1, 1).unwrap();
+        assert_eq!(Date::from_bahai(naw_ruz).unwrap().strftime("%F"), ~"1844-03-21");
+        assert_eq!(Date::from_ymd(1844, 3, 21).unwrap().to_bahai(), Some(naw_ruz));
+        assert_eq!(Date::from_ymd(1844, 3, 20).unwrap().to_bahai(), None);
+        assert_eq!(Date::from_ymd(1800, 6, 1).unwrap().to_bahai(), None);
+        assert_eq!(Date::from_ymd(0, 1, 1).unwrap().to_bahai(), None);
+    }
+
+    #[test]
+    fn from_stem_branch_year() {
+        let dates = Date::from_stem_branch_year_around(Jia, Zi, 2, 13, 2009);
+        assert_eq!(dates.len(), 2);
+        assert_eq!(dates[0].strftime("%Y-%m-%d"), ~"1984-02-13");
+        assert_eq!(dates[1].strftime("%Y-%m-%d"), ~"2044-02-13");
+
+        /* 1960 and 2020 are leap years */
+        let dates = Date::from_stem_branch_year_around(Geng, Zi, 2, 29, 2009);
+        assert_eq!(dates.len(), 2);
+        assert_eq!(dates[0].strftime("%Y-%m-%d"), ~"1960-02-29");
+        assert_eq!(dates[1].strftime("%Y-%m-%d"), ~"2020-02-29");
+
+        assert_eq!(Date::from_stem_branch_year_around(Jia, Chou, 1, 1, 2009).len(), 0);
+        assert_eq!(Date::from_stem_branch_year_around(Jia, Zi, 2, 30, 2009).len(), 0);
+    }
+
+    #[test]
+    fn decimal_date() {
+        let d = Date::from_decimal_date(2009.0).unwrap();
+        assert_eq!(d.strftime("%Y-%m-%d %H:%M:%S"), ~"2009-01-01 00:00:00");
+        assert_eq!(d.to_decimal_date(), 2009.0);
+
+        let d = Date::from_decimal_date(2009.5).unwrap();
+        assert_eq!(d.strftime("%Y-%m-%d %H:%M:%S"), ~"2009-07-02 12:00:00");
+        assert_eq!(d.to_decimal_date(), 2009.5);
+
+        /* Half of a leap year is 183 days */
+        let d = Date::from_decimal_date(2008.5).unwrap();
+        assert_eq!(d.strftime("%Y-%m-%d %H:%M:%S"), ~"2008-07-02 00:00:00");
+
+        let d = Date::from_decimal_date(2009.12).unwrap();
+        assert_eq!(d.strftime("%Y-%m-%d"), ~"2009-02-13");
+
+        let d = Date::from_decimal_date(1969.5).unwrap();
+        assert_eq!(d.strftime("%Y-%m-%d %H:%M:%S"), ~"1969-07-02 12:00:00");
+        assert_eq!(d.to_decimal_date(), 1969.5);
+        assert_eq!(Date::from_decimal_date(-44.2).unwrap_err(), InvalidYear);
+    }
+
+    #[test]
+    fn from_parts_opt() {
+        let d = Date::from_parts_opt(Some(2009), Some(2), Some(13), Some(23), Some(31),
+                                     Some(30), Some(543)).unwrap();
+        assert_eq!(d.get_time(), 1234567890543);
+
+        let today = Date::from_timestamp_millis(1234567890543).get_cal();
+        let d = Date::from_parts_opt_on(None, Some(12), Some(25), None, None, None, None,
+                                        &today).unwrap();
+        assert_eq!(d.strftime("%Y-%m-%d %H:%M:%S"), ~"2009-12-25 00:00:00");
+
+        let d = Date::from_parts_opt_on(Some(2008), None, None, Some(8), None, None, None,
+                                        &today).unwrap();
+        assert_eq!(d.strftime("%Y-%m-%d %H:%M:%S"), ~"2008-02-13 08:00:00");
+
+        let d = Date::from_parts_opt(None, Some(12), Some(25), None, None, None, None).unwrap();
+        assert_eq!(d.strftime("%m-%d %H:%M:%S"), ~"12-25 00:00:00");
+
+        let err = Date::from_parts_opt(Some(2009), Some(13), None, None, None, None, None);
+        assert_eq!(err.unwrap_err(), InvalidMonth);
+    }
+
+    #[test]
+    fn strftime_time_and_date_only() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.strftime_time_only("%H:%M:%S"), Ok(~"23:31:30"));
+        assert_eq!(d.strftime_time_only("%Y %H:%M"), Err(UnexpectedSpecifier('Y')));
+        assert_eq!(d.strftime_date_only("%d/%m/%Y"), Ok(~"13/02/2009"));
+        assert_eq!(d.strftime_date_only("%F %T"), Err(UnexpectedSpecifier('T')));
+    }
+
+    #[test]
+    fn from_timestamp_string() {
+        fn millis(s: &str) -> i64 {
+            Date::from_timestamp_string(s).unwrap().get_time()
+        }
+
+        let t = 1234567890000;
+        assert_eq!(millis("2009-02-13T23:31:30Z"), t);
+        assert_eq!(millis("2009-02-14T05:01:30+05:30"), t);
+        assert_eq!(millis("2009-02-13T23:31:30"), t);
+        assert_eq!(millis("2009-02-13 23:31:30"), t);
+        assert_eq!(millis("Fri, 13 Feb 2009 23:31:30 +0000"), t);
+        assert_eq!(millis("Fri, 13 Feb 2009 23:31:30 GMT"), t);
+        assert_eq!(millis("13 Feb 2009 18:31:30 -0500"), t);
+        assert_eq!(millis("Fri Feb 13 23:31:30 2009"), t);
+        assert_eq!(millis("1234567890"), t);
+        assert_eq!(millis("1234567890000"), t);
+        assert_eq!(millis("-1234567890"), -t);
+
+        let day = 1234483200000;
+        assert_eq!(millis("2009-02-13"), day);
+        assert_eq!(millis("13/02/2009"), day);
+        assert_eq!(millis("02/13/2009"), day);
+        assert_eq!(millis("13-Feb-2009"), day);
+        assert_eq!(millis("Feb 13 2009"), day);
+        assert_eq!(millis("20090213"), day);
+        assert_eq!(millis("2009/02/13"), day);
+
+        assert_eq!(Date::from_timestamp_string("01/02/2009").unwrap_err(), AmbiguousFormat);
+        assert_eq!(Date::from_timestamp_string("02/02/2009").unwrap().strftime("%F"),
+                   ~"2009-02-02");
+        assert_eq!(Date::from_timestamp_string("32/13/2009").unwrap_err(), InvalidValue);
+        assert_eq!(Date::from_timestamp_string("yesterday").unwrap_err(), InvalidFormat);
+    }
+
+    #[test]
+    fn parse_relative() {
+        let friday = Date::from_proleptic_gregorian(2009, 2, 13, 23, 31, 30, 0).unwrap();
+        let rel = |expr: &str| Date::parse_relative(expr, &friday).unwrap().strftime("%a %F %T");
+
+        assert_eq!(rel("T+2BD"), ~"Tue 2009-02-17 23:31:30");
+        assert_eq!(rel("+2BD"), ~"Tue 2009-02-17 23:31:30");
+        assert_eq!(rel("T-5BD"), ~"Fri 2009-02-06 23:31:30");
+        assert_eq!(rel("T+0BD"), ~"Fri 2009-02-13 23:31:30");
+        assert_eq!(rel("T+3"), ~"Mon 2009-02-16 23:31:30");
+        assert_eq!(rel("D-30"), ~"Wed 2009-01-14 23:31:30");
+        assert_eq!(rel("+2W"), ~"Fri 2009-02-27 23:31:30");
+        assert_eq!(rel("+6M"), ~"Thu 2009-08-13 23:31:30");
+        assert_eq!(rel("-14M"), ~"Thu 2007-12-13 23:31:30");
+        assert_eq!(rel("T+1Y"), ~"Sat 2010-02-13 23:31:30");
+        assert_eq!(rel("T"), ~"Fri 2009-02-13 23:31:30");
+
+        let jan31 = Date::from_proleptic_gregorian(2008, 1, 31, 0, 0, 0, 0).unwrap();
+        assert_eq!(Date::parse_relative("+1M", &jan31).unwrap().strftime("%F"), ~"2008-02-29");
+        assert_eq!(Date::parse_relative("+13M", &jan31).unwrap().strftime("%F"), ~"2009-02-28");
+
+        assert_eq!(Date::parse_relative("T+", &friday).unwrap_err(), InvalidFormat);
+        assert_eq!(Date::parse_relative("T*2", &friday).unwrap_err(), InvalidFormat);
+        assert_eq!(Date::parse_relative("T+2Q", &friday).unwrap_err(), InvalidFormat);
+        assert_eq!(rel("T-40Y"), ~"Thu 1969-02-13 23:31:30");
+        assert_eq!(rel("T-15000D"), ~"Sat 1968-01-20 23:31:30");
+        assert_eq!(Date::parse_relative("T-2010Y", &friday).unwrap_err(), InvalidValue);
+        assert_eq!(Date::parse_relative("T-800000D", &friday).unwrap_err(), InvalidValue);
+    }
+
+    #[test]
+    fn format_epoch() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert!(d.format_epoch_sci().starts_with("1.2345"));
+        assert_eq!(d.format_epoch_sci(), ~"1.234568e9 s");
+        assert_eq!(Date::from_timestamp_millis(0).format_epoch_sci(), ~"0.000000e0 s");
+        assert_eq!(Date::from_timestamp_millis(250).format_epoch_sci(), ~"2.500000e-1 s");
+        assert_eq!(Date::from_timestamp_millis(9999999999).format_epoch_sci(), ~"1.000000e7 s");
+        assert_eq!(Date::from_timestamp_millis(-9999999999).format_epoch_sci(),
+                   ~"-1.000000e7 s");
+
+        assert_eq!(d.format_epoch_human(), ~"1.23B s");
+        assert_eq!(Date::from_timestamp_millis(86400000).format_epoch_human(), ~"86.40K s");
+        assert_eq!(Date::from_timestamp_millis(45500).format_epoch_human(), ~"45.50 s");
+    }
+
+    #[test]
+    fn months_since_epoch() {
+        let d = Date::from_proleptic_gregorian(2009, 2, 1, 0, 0, 0, 0).unwrap();
+        assert_eq!(d.months_since_epoch(), 469);
+        assert_eq!(Date::from_timestamp_millis(1234567890543).months_since_epoch(), 469);
+        assert_eq!(Date::unix_epoch().months_since_epoch(), 0);
+
+        assert_eq!(Date::from_months_since_epoch(469).strftime("%Y-%m"), ~"2009-02");
+        assert_eq!(Date::from_months_since_epoch(469).get_time(), d.get_time());
+        assert_eq!(Date::from_months_since_epoch(0).get_time(), 0);
+        assert_eq!(Date::from_months_since_epoch(-1).strftime("%F"), ~"1969-12-01");
+        assert_eq!(Date::from_timestamp_millis(-1).months_since_epoch(), -1);
+        assert_eq!(Date::from_months_since_epoch(479).strftime("%F %T"), ~"2009-12-01 00:00:00");
+    }
+
+    #[test]
+    fn weeks_since_epoch() {
+        let monday = Date::from_proleptic_gregorian(2009, 2, 9, 0, 0, 0, 0).unwrap();
+        let weeks = monday.weeks_since_epoch();
+        assert_eq!(weeks, 2041);
+        assert_eq!(Date::from_timestamp_millis(1234567890543).weeks_since_epoch(), weeks);
+        let sunday = Date::from_proleptic_gregorian(2009, 2, 15, 23, 59, 59, 999).unwrap();
+        assert_eq!(sunday.weeks_since_epoch(), weeks);
+        let next = Date::from_proleptic_gregorian(2009, 2, 16, 0, 0, 0, 0).unwrap();
+        assert_eq!(next.weeks_since_epoch(), weeks + 1);
+
+        assert_eq!(Date::from_weeks_since_epoch(weeks).get_time(), monday.get_time());
+        assert_eq!(Date::from_weeks_since_epoch(weeks).strftime("%a %F"), ~"Mon 2009-02-09");
+
+        /* Thursday 1st to Sunday 4th of January 1970 */
+        assert_eq!(Date::unix_epoch().weeks_since_epoch(), 0);
+        assert_eq!(Date::from_timestamp_millis(3 * 86400000).weeks_since_epoch(), 0);
+        assert_eq!(Date::from_timestamp_millis(4 * 86400000).weeks_since_epoch(), 1);
+        assert_eq!(Date::from_weeks_since_epoch(1).strftime("%a %F"), ~"Mon 1970-01-05");
+        assert_eq!(Date::from_weeks_since_epoch(0).strftime("%a %F"), ~"Mon 1969-12-29");
+        assert_eq!(Date::from_timestamp_millis(-4 * 86400000).weeks_since_epoch(), 0);
+        assert_eq!(Date::from_timestamp_millis(-5 * 86400000).weeks_since_epoch(), -1);
+    }
+
+    #[test]
+    fn with_iana_tz() {
+        let d = Date::from_timestamp_millis(1234567890543);
+
+        let ist = d.with_iana_tz("Asia/Kolkata").unwrap();
+        assert_eq!(ist.get_offset(), 330);
+        assert_eq!(ist.get_date().get_time(), d.get_time());
+        assert_eq!(ist.strftime("%F %T %z"), ~"2009-02-14 05:01:30 +0530");
+        assert_eq!(ist.get_cal().get_msec(), 543);
+
+        let est = d.with_iana_tz("America/New_York").unwrap();
+        assert_eq!(est.strftime("%a %F %T %z"), ~"Fri 2009-02-13 18:31:30 -0500");
+        let nst = d.with_iana_tz("America/St_Johns").unwrap();
+        assert_eq!(nst.strftime("%T %z"), ~"20:01:30 -0330");
+
+        assert_eq!(d.with_iana_tz("Mars/Olympus_Mons").unwrap_err(), InvalidTimezone);
+        let est = Date::unix_epoch().with_iana_tz("America/New_York").unwrap();
+        assert_eq!(est.strftime("%F %T %z"), ~"1969-12-31 19:00:00 -0500");
+        assert_eq!(est.strftime("%Z"), ~"America/New_York");
+    }
+
+    #[test]
+    fn with_timezone() {
+        let d = Date::from_timestamp_millis(1234567890543);
+
+        let ist = d.with_timezone(&FixedOffset::new(5, 30));
+        assert_eq!(ist.get_offset(), 330);
+        assert_eq!(ist.get_zone(), ~"+05:30");
+        assert_eq!(ist.get_date(), d);
+        assert_eq!(ist.strftime("%F %T %z %Z"), ~"2009-02-14 05:01:30 +0530 +05:30");
+
+        let nst = d.with_timezone(&FixedOffset::new(-3, -30));
+        assert_eq!(nst.strftime("%F %T %z"), ~"2009-02-13 20:01:30 -0330");
+
+        let utc = d.with_timezone(&FixedOffset::utc());
+        assert_eq!(utc.strftime("%F %T %z %Z"), ~"2009-02-13 23:31:30 +0000 UTC");
+    }
+
+    #[test]
+    fn from_calendar_position() {
+        /* February 2009 starts on a Sunday and has 28 days */
+        let pos = |row: uint, col: uint| {
+            match Date::from_calendar_position(2009, 2, row, col, Monday) {
+                Some(d) => d.strftime("%a %d"),
+                None => ~""
+            }
+        };
+        assert_eq!(pos(0, 0), ~"");
+        assert_eq!(pos(0, 5), ~"");
+        assert_eq!(pos(0, 6), ~"Sun 01");
+        assert_eq!(pos(1, 0), ~"Mon 02");
+        assert_eq!(pos(1, 5), ~"Sat 07");
+        assert_eq!(pos(4, 5), ~"Sat 28");
+        assert_eq!(pos(4, 6), ~"");
+        assert_eq!(pos(5, 0), ~"");
+
+        let d = Date::from_calendar_position(2009, 2, 0, 0, Sunday).unwrap();
+        assert_eq!(d.strftime("%a %F"), ~"Sun 2009-02-01");
+        let d = Date::from_calendar_position(2009, 2, 3, 6, Sunday).unwrap();
+        assert_eq!(d.strftime("%a %F"), ~"Sat 2009-02-28");
+        assert!(Date::from_calendar_position(2009, 2, 4, 0, Sunday).is_none());
+
+        assert!(Date::from_calendar_position(2009, 13, 0, 0, Sunday).is_none());
+        assert!(Date::from_calendar_position(2009, 2, 0, 7, Sunday).is_none());
+        assert!(Date::from_calendar_position(2009, 2, 6, 0, Sunday).is_none());
+    }
+
+    #[test]
+    fn day_of_epoch() {
+        assert_eq!(Date::from_day_of_epoch(0).strftime("%F %T"), ~"1970-01-01 00:00:00");
+        assert_eq!(Date::from_day_of_epoch(1).strftime("%F %T"), ~"1970-01-02 00:00:00");
+        assert_eq!(Date::from_day_of_epoch(-1).strftime("%F %T"), ~"1969-12-31 00:00:00");
+        assert_eq!(Date::from_timestamp_millis(-1).day_of_epoch(), -1);
+
+        let midnight = Date::from_proleptic_gregorian(2009, 2, 13, 0, 0, 0, 0).unwrap();
+        let last = Date::from_proleptic_gregorian(2009, 2, 13, 23, 59, 59, 999).unwrap();
+        assert_eq!(midnight.day_of_epoch(), 14288);
+        assert_eq!(last.day_of_epoch(), 14288);
+        assert_eq!(Date::from_day_of_epoch(14288).get_time(), midnight.get_time());
+    }
+
+    #[test]
+    fn from_date_and_time_str() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        let t = Date::from_date_and_time_str(&d, "2:30 PM").unwrap();
+        assert_eq!(t.strftime("%F %T"), ~"2009-02-13 14:30:00");
+        let t = Date::from_date_and_time_str(&d, "08:15:00.250").unwrap();
+        assert_eq!(t.get_time(), 1234512900250);
+
+        assert_eq!(Date::from_date_and_time_str(&d, "25:00").unwrap_err(), InvalidValue);
+        assert_eq!(Date::from_date_and_time_str(&d, "noon").unwrap_err(), InvalidFormat);
+    }
+
+    #[test]
+    fn quarters() {
+        let day = |month: uint, mday: uint| {
+            Date::from_proleptic_gregorian(2009, month, mday, 13, 0, 0, 0).unwrap()
+        };
+        let quarters = [(1, 1, 1), (3, 31, 1), (4, 1, 2), (6, 30, 2), (7, 1, 3),
+                        (9, 30, 3), (10, 1, 4), (12, 31, 4)];
+        for &(month, mday, quarter) in quarters.iter() {
+            assert_eq!(day(month, mday).quarter(), quarter);
+        }
+        assert_eq!(Date::from_timestamp_millis(-1).quarter(), 4);
+        let starts = [(2, 13, "2009-01-01"), (4, 1, "2009-04-01"), (9, 30, "2009-07-01"),
+                      (12, 31, "2009-10-01")];
+        for &(month, mday, start) in starts.iter() {
+            assert_eq!(day(month, mday).quarter_start().strftime("%F %T"),
+                       format!("{} 00:00:00", start));
+        }
+        let ends = [(1, 1, "2009-03-31"), (6, 30, "2009-06-30"), (8, 15, "2009-09-30"),
+                    (10, 1, "2009-12-31")];
+        for &(month, mday, end) in ends.iter() {
+            let d = day(month, mday).quarter_end();
+            assert_eq!(d.strftime("%F %T"), format!("{} 23:59:59", end));
+            assert_eq!(d.get_cal().get_msec(), 999);
+        }
+
+        /* Fiscal year starting in April: quarters start in Apr, Jul, Oct and Jan */
+        assert_eq!(day(2, 13).fiscal_quarter_start(4).strftime("%F"), ~"2009-01-01");
+        assert_eq!(day(5, 1).fiscal_quarter_start(4).strftime("%F"), ~"2009-04-01");
+        /* Fiscal year starting in February: Feb, May, Aug and Nov */
+        assert_eq!(day(1, 15).fiscal_quarter_start(2).strftime("%F"), ~"2008-11-01");
+        assert_eq!(day(12, 31).fiscal_quarter_start(2).strftime("%F"), ~"2009-11-01");
+        assert_eq!(day(7, 31).fiscal_quarter_start(11).strftime("%F"), ~"2009-05-01");
+
+        let d = Date::from_proleptic_gregorian(1969, 2, 13, 0, 0, 0, 0).unwrap();
+        assert_eq!(d.quarter_start().strftime("%F"), ~"1969-01-01");
+        assert_eq!(d.fiscal_quarter_start(2).strftime("%F"), ~"1969-02-01");
+        assert_eq!(d.fiscal_quarter_start(4).strftime("%F"), ~"1969-01-01");
+        assert_eq!(Date::unix_epoch().fiscal_quarter_start(2).strftime("%F"), ~"1969-11-01");
+    }
+
+    #[test]
+    fn from_ansi_c_asctime() {
+        let d = Date::from_ansi_c_asctime("Fri Feb 13 23:31:30 2009\n").unwrap();
+        assert_eq!(d.get_time(), 1234567890000);
+        let d = Date::from_ansi_c_asctime("Tue Feb  3 23:31:30 2009").unwrap();
+        assert_eq!(d.strftime("%F"), ~"2009-02-03");
+
+        assert_eq!(Date::from_ansi_c_asctime("Tue Feb 3 23:31:30 2009").unwrap_err(),
+                   InvalidFormat);
+        assert_eq!(Date::from_ansi_c_asctime("Tue Feb 03 23:31:30 2009").unwrap_err(),
+                   InvalidFormat);
+        assert_eq!(Date::from_ansi_c_asctime("Fri Feb 13 23:31:30 2009\n\n").unwrap_err(),
+                   InvalidFormat);
+
+        let mut ms = 0i64;
+        while ms < 7258118400000 {
+            let d = Date::from_timestamp_millis(ms);
+            let back = Date::from_ansi_c_asctime(d.ctime()).unwrap();
+            assert_eq!(back.get_time() / 1000, d.get_time() / 1000);
+            ms += 7777777777;
+        }
+    }
+
+    #[test]
+    fn zulu_string() {
+        let d = Date::from_timestamp_millis(1234567890000);
+        assert_eq!(d.to_zulu_string(), ~"20090213T233130Z");
+        assert_eq!(Date::from_zulu_string(d.to_zulu_string()), Ok(d));
+
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(Date::from_zulu_string(d.to_zulu_string()).unwrap().get_time(),
+                   1234567890000);
+        assert_eq!(Date::from_zulu_string("20090213T233130.543Z"), Ok(d));
+
+        assert_eq!(Date::from_zulu_string("20090213T233130").unwrap_err(), InvalidFormat);
+        assert_eq!(Date::from_zulu_string("20090213T233130X").unwrap_err(), InvalidFormat);
+        assert_eq!(Date::from_zulu_string("20090213T233130.54Z").unwrap_err(), InvalidFormat);
+        assert_eq!(Date::from_zulu_string("20090213T233130.5a3Z").unwrap_err(), InvalidFormat);
+        assert_eq!(Date::from_zulu_string("2009-02-13T23:31:30Z").unwrap_err(), InvalidFormat);
+        assert_eq!(Date::from_zulu_string("20090230T233130Z").unwrap_err(), InvalidValue);
+    }
+
+    #[test]
+    fn epoch_bytes() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.to_epoch_millis_u64_be(), [0, 0, 1, 31, 113, 251, 6, 111]);
+        assert_eq!(d.to_epoch_secs_u32_be(), [73, 150, 2, 210]);
+
+        let buf = do io::with_bytes_writer |wr| {
+            wr.write(d.to_epoch_millis_u64_be());
+            wr.write(d.to_epoch_secs_u32_be());
+        };
+        assert_eq!(buf.len(), 12);
+
+        let mut millis = [0u8, ..8];
+        let mut secs = [0u8, ..4];
+        for i in range(0u, 8) {
+            millis[i] = buf[i];
+        }
+        for i in range(0u, 4) {
+            secs[i] = buf[8 + i];
+        }
+        assert_eq!(Date::from_epoch_millis_u64_be(&millis), d);
+        assert_eq!(Date::from_epoch_secs_u32_be(&secs).get_time(), 1234567890000);
+
+        let far = Date::from_timestamp_millis(5000000000000);
+        assert_eq!(far.to_epoch_secs_u32_be(), [255, 255, 255, 255]);
+        assert_eq!(Date::from_epoch_secs_u32_be(&[255, 255, 255, 255]).get_time(),
+                   4294967295000);
+    }
+
+    #[test]
+    fn days_in_range() {
+        let start = Date::from_calendar(2009, 1, 1, 23, 59, 0, 0);
+        let end = Date::from_calendar(2009, 1, 3, 0, 1, 0, 0);
+        assert_eq!(Date::days_in_range(&start, &end), 3);
+
+        let start = Date::from_calendar(2009, 1, 1, 0, 0, 0, 0);
+        let end = Date::from_calendar(2009, 1, 3, 0, 0, 0, 0);
+        assert_eq!(Date::days_in_range(&start, &end), 2);
+
+        assert_eq!(Date::days_in_range(&start, &start), 0);
+        assert_eq!(Date::days_in_range(&end, &start), 0);
+    }
+
+    #[test]
+    fn from_secs_nanos() {
+        assert_eq!(Date::from_secs_nanos(1234567890, 543000000).unwrap(),
+                   Date::from_timestamp_millis(1234567890543));
+        assert_eq!(Date::from_secs_nanos(1234567890, 543999999).unwrap(),
+                   Date::from_timestamp_millis(1234567890543));
+        assert_eq!(Date::from_secs_nanos(0, 0).unwrap(), Date::unix_epoch());
+        assert_eq!(Date::from_secs_nanos(1234567890, 1000000000).unwrap_err(),
+                   InvalidNanosecond);
+        assert_eq!(Date::from_secs_nanos(-1, 500000000).unwrap().get_time(), -500);
+        assert_eq!(Date::from_secs_nanos(-70000000000, 0).unwrap_err(), InvalidYear);
+        assert_eq!(Date::from_secs_nanos(i64::max_value / 100, 0).unwrap_err(), InvalidYear);
+        assert_eq!(Date::from_secs_nanos(i64::max_value / 1000, 999000000).unwrap_err(),
+                   InvalidYear);
+    }
+
+    #[test]
+    fn from_ctime_2digit_year() {
+        let year = |s: &str, pivot: u32| {
+            Date::from_ctime_2digit_year(s, pivot).unwrap().strftime("%Y")
+        };
+        assert_eq!(year("Fri Feb 13 23:31:30 09", 1970), ~"2009");
+        assert_eq!(year("Thu Feb 13 23:31:30 69", 1970), ~"2069");
+        assert_eq!(year("Fri Feb 13 23:31:30 70", 1970), ~"1970");
+        assert_eq!(year("Sat Feb 13 23:31:30 99", 1970), ~"1999");
+
+        assert_eq!(year("Fri Feb 13 23:31:30 09", 1950), ~"2009");
+        assert_eq!(year("Thu Feb 13 23:31:30 69", 1950), ~"1969");
+        assert_eq!(year("Fri Feb 13 23:31:30 70", 1950), ~"1970");
+        assert_eq!(year("Sat Feb 13 23:31:30 99", 1950), ~"1999");
+
+        assert_eq!(year("Fri Feb 13 23:31:30 09", 2000), ~"2009");
+        assert_eq!(year("Thu Feb 13 23:31:30 69", 2000), ~"2069");
+        assert_eq!(year("Fri Feb 13 23:31:30 99", 2000), ~"2099");
+
+        let d = Date::from_ctime_2digit_year("Fri Feb 13 23:31:30 09\n", 1970).unwrap();
+        assert_eq!(d.get_time(), 1234567890000);
+
+        assert_eq!(Date::from_ctime_2digit_year("Fri Feb 13 23:31:30 2009", 1970).unwrap_err(),
+                   InvalidFormat);
+        assert_eq!(Date::from_ctime_2digit_year("Fri Feb 13 23:31:30  9", 1970).unwrap_err(),
+                   InvalidFormat);
+    }
+
+    #[test]
+    fn duration_arithmetic() {
+        let today = Date::from_proleptic_gregorian(2009, 2, 13, 23, 31, 30, 0).unwrap();
+        let tomorrow = today + Duration::from_days(1);
+        assert_eq!(tomorrow.strftime("%F %T"), ~"2009-02-14 23:31:30");
+        assert_eq!(today + Duration::from_days(-1), today - Duration::from_days(1));
+        assert_eq!((today - Duration::from_hours(24)).strftime("%F %T"),
+                   ~"2009-02-12 23:31:30");
+
+        let eve = Date::from_proleptic_gregorian(2008, 12, 31, 23, 0, 0, 0).unwrap();
+        assert_eq!((eve + Duration::from_hours(2)).strftime("%F %T"), ~"2009-01-01 01:00:00");
+
+        let feb28 = Date::from_proleptic_gregorian(2008, 2, 28, 12, 0, 0, 0).unwrap();
+        assert_eq!((feb28 + Duration::from_days(1)).strftime("%F"), ~"2008-02-29");
+        assert_eq!((feb28 + Duration::from_days(2)).strftime("%F"), ~"2008-03-01");
+
+        let epoch = Date::unix_epoch();
+        let before = epoch - Duration::from_secs(1);
+        assert_eq!(before.strftime("%F %T"), ~"1969-12-31 23:59:59");
+        assert_eq!(before.get_time(), -1000);
+
+        assert_eq!(tomorrow.duration_since(&today), Duration::from_days(1));
+        assert_eq!(today.duration_since(&tomorrow), Duration::from_hours(-24));
+        assert_eq!(before.duration_since(&epoch).as_millis(), -1000);
+    }
+
+    #[test]
+    fn strptime() {
+        let d = Date::strptime("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(d.get_time(), 1234567890000);
+        assert_eq!(Date::strptime("2009-044 23:31:30", "%Y-%j %T").unwrap(), d);
+        assert_eq!(Date::strptime("1234567890", "%s").unwrap(), d);
+        assert_eq!(Date::strptime("-1", "%s").unwrap().get_time(), -1000);
+
+        let formats = ["%Y-%m-%d %H:%M:%S", "%d/%m/%Y %T", "%Y%m%d%H%M%S", "%Y-%j %T",
+                       "%s", "%a, %d %b %Y %T %z"];
+        let dates = [d, Date::unix_epoch(), Date::from_timestamp_millis(-2208988800000),
+                     Date::from_timestamp_millis(4102444799000)];
+        for fmt in formats.iter() {
+            for date in dates.iter() {
+                assert_eq!(Date::strptime(date.strftime(*fmt), *fmt), Ok(*date));
+            }
+        }
+
+        assert_eq!(Date::strptime("2009-13-13", "%Y-%m-%d").unwrap_err(), InvalidValue);
+        assert_eq!(Date::strptime("2009-366", "%Y-%j").unwrap_err(), InvalidValue);
+        assert_eq!(Date::strptime("2009-02-13", "%Y-%m-%q").unwrap_err(),
+                   UnknownSpecifier('q'));
+        assert_eq!(Date::strptime("2009-02-13Z", "%Y-%m-%d").unwrap_err(),
+                   UnexpectedInput(10, ~"end of input", Some('Z')));
+        assert_eq!(Date::strptime("2009-XY-13", "%Y-%m-%d").unwrap_err().to_str(),
+                   ~"parse error at position 5: expected digit, got 'X'");
+    }
+
+    #[test]
+    fn from_ymd() {
+        let d = Date::from_ymd(2009, 2, 13).unwrap();
+        assert_eq!(d.strftime("%F %T"), ~"2009-02-13 00:00:00");
+        let d = Date::from_ymd_hms(2009, 2, 13, 23, 31, 30).unwrap();
+        assert_eq!(d.get_time(), 1234567890000);
+        assert_eq!(Date::from_ymd(1969, 12, 31).unwrap().get_time(), -86400000);
+
+        assert_eq!(Date::from_ymd(2009, 13, 1).unwrap_err(), InvalidMonth);
+        assert_eq!(Date::from_ymd(2009, 0, 1).unwrap_err(), InvalidMonth);
+        assert_eq!(Date::from_ymd(2009, 2, 29).unwrap_err(), InvalidDay);
+        assert!(Date::from_ymd(2008, 2, 29).is_ok());
+        assert_eq!(Date::from_ymd(-1, 1, 1).unwrap_err(), InvalidYear);
+        assert_eq!(Date::from_ymd_hms(2009, 2, 13, 24, 0, 0).unwrap_err(), InvalidHour);
+
+        let today = Date::from_timestamp_millis(1234567890543).get_cal();
+        let d = Date::from_hms_on(12, 30, 0, &today).unwrap();
+        assert_eq!(d.strftime("%F %T"), ~"2009-02-13 12:30:00");
+        assert_eq!(Date::from_hms_on(12, 60, 0, &today).unwrap_err(), InvalidMinute);
+        assert_eq!(Date::from_hms(12, 30, 0).unwrap().strftime("%T"), ~"12:30:00");
+        assert_eq!(Date::from_hms(12, 60, 0).unwrap_err(), InvalidMinute);
+    }
+
+    #[test]
+    fn parse_unix_date() {
+        /* date -u -d @1234567890 */
+        let d = Date::parse_unix_date("Fri Feb 13 23:31:30 UTC 2009").unwrap();
+        assert_eq!(d.get_time(), 1234567890000);
+        assert_eq!(Date::parse_unix_date("Fri Feb 13 23:31:30 2009").unwrap(), d);
+        assert_eq!(Date::parse_unix_date("Fri Feb 13 18:31:30 EST 2009").unwrap(), d);
+
+        /* date -u -d @1706951100 */
+        let d = Date::parse_unix_date("Sat Feb  3 09:05:00 UTC 2024").unwrap();
+        assert_eq!(d.get_time(), 1706951100000);
+
+        assert_eq!(Date::parse_unix_date("Fri Feb 13 23:31:30 XYZ 2009").unwrap_err(),
+                   UnknownTimezone);
+        assert_eq!(Date::parse_unix_date("2009-02-13 23:31:30").unwrap_err(), InvalidFormat);
+    }
+
+    #[test]
+    fn pg_timestamp() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.to_pg_timestamp(), ~"2009-02-13 23:31:30.543");
+        assert_eq!(Date::from_timestamp_millis(1234567890000).to_pg_timestamp(),
+                   ~"2009-02-13 23:31:30.000");
+        assert_eq!(Date::from_pg_timestamp(d.to_pg_timestamp()).unwrap(), d);
+
+        let pg = |s: &str| Date::from_pg_timestamp(s).unwrap().get_time();
+        assert_eq!(pg("2009-02-13 23:31:30"), 1234567890000);
+        assert_eq!(pg("2009-02-13 23:31:30.543210"), 1234567890543);
+        assert_eq!(pg("2009-02-13 23:31:30.5"), 1234567890500);
+        assert_eq!(pg("2009-02-14 05:01:30.543+05:30"), 1234567890543);
+        assert_eq!(pg("2009-02-13 15:31:30-08"), 1234567890000);
+        assert_eq!(pg("2009-02-13 23:31:30+00"), 1234567890000);
+
+        assert_eq!(Date::from_pg_timestamp("2009-02-13").unwrap_err(), InvalidFormat);
+        assert_eq!(Date::from_pg_timestamp("2009-02-13 23:31:30.").unwrap_err(),
+                   InvalidFormat);
+        assert_eq!(Date::from_pg_timestamp("2009-02-13 23:31:30.5432101").unwrap_err(),
+                   InvalidFormat);
+        assert_eq!(Date::from_pg_timestamp("2009-02-13 23:31:30 UTC").unwrap_err(),
+                   InvalidFormat);
+        assert_eq!(Date::from_pg_timestamp("2009-02-30 23:31:30").unwrap_err(), InvalidValue);
+    }
+
+    #[test]
+    fn rfc3339() {
+        let d = Date::from_timestamp_millis(1234567890000);
+        assert_eq!(d.rfc3339(), ~"2009-02-13T23:31:30Z");
+        assert_eq!(Date::from_rfc3339("2009-02-13T23:31:30Z").unwrap(), d);
+        assert_eq!(Date::from_rfc3339("2009-02-14T05:01:30+05:30").unwrap(), d);
+        assert_eq!(Date::from_rfc3339("2009-02-13T18:31:30-05:00").unwrap(), d);
+        assert_eq!(Date::from_rfc3339("2009-02-13T23:31:30+00:00").unwrap(), d);
+
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.rfc3339(), ~"2009-02-13T23:31:30.543Z");
+        assert_eq!(Date::from_rfc3339(d.rfc3339()).unwrap(), d);
+        assert_eq!(Date::from_rfc3339("2009-02-13T23:31:30.543210987Z").unwrap(), d);
+        assert_eq!(Date::from_rfc3339("2009-02-13T15:31:30.5-08:00").unwrap().get_time(),
+                   1234567890500);
+
+        assert_eq!(Date::from_rfc3339("2009-02-13 23:31:30Z").unwrap_err(), InvalidFormat);
+        assert_eq!(Date::from_rfc3339("2009-02-13T23:31:30").unwrap_err(), InvalidFormat);
+        assert_eq!(Date::from_rfc3339("2009-02-13T23:31:30+0530").unwrap_err(), InvalidFormat);
+        assert_eq!(Date::from_rfc3339("2009-02-13T23:31:30.Z").unwrap_err(), InvalidFormat);
+        assert_eq!(Date::from_rfc3339("2009-02-13T23:31:30+24:00").unwrap_err(), InvalidValue);
+        assert_eq!(Date::from_rfc3339("2009-13-13T23:31:30Z").unwrap_err(), InvalidValue);
+    }
+
+    #[test]
+    fn mongodb() {
+        let d = Date::from_mongodb_ms(1234567890543);
+        assert_eq!(d.get_time(), 1234567890543);
+        assert_eq!(d.to_mongodb_extended_json(),
+                   ~"{ \"$date\": { \"$numberLong\": \"1234567890543\" } }");
+        assert_eq!(Date::from_mongodb_ms(-1).to_mongodb_extended_json(),
+                   ~"{ \"$date\": { \"$numberLong\": \"-1\" } }");
+
+        assert_eq!(Date::from_mongodb_isodate("ISODate(\"2009-02-13T23:31:30.543Z\")").unwrap(),
+                   d);
+        assert_eq!(Date::from_mongodb_isodate("ISODate(\"1970-01-01T00:00:00Z\")").unwrap(),
+                   Date::from_mongodb_ms(0));
+        assert_eq!(Date::from_mongodb_isodate("ISODate(\"2009-02-13T23:31:30\")").unwrap_err(),
+                   InvalidFormat);
+        assert_eq!(Date::from_mongodb_isodate("\"2009-02-13T23:31:30.543Z\"").unwrap_err(),
+                   InvalidFormat);
+        assert_eq!(Date::from_mongodb_isodate("ISODate(\")").unwrap_err(), InvalidFormat);
+    }
+
+    #[test]
+    fn strftime_with_era() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.strftime_with_era("%EC %Ey %EY"), Ok(~"AD 2009 2009 AD"));
+        assert_eq!(d.strftime_with_era("%d %b %EY, %T"), Ok(~"13 Feb 2009 AD, 23:31:30"));
+        assert_eq!(d.strftime_with_era("%Ed/%Em"), Ok(~"13/02"));
+        assert_eq!(d.strftime_with_era("%EY%%"), Ok(~"2009 AD%"));
+
+        let d = Date::from_ymd(1, 1, 1).unwrap();
+        assert_eq!(d.strftime_with_era("%EY %EC %Ey"), Ok(~"0001 AD AD 1"));
+        let d = Date::from_ymd(0, 12, 31).unwrap();
+        assert_eq!(d.strftime_with_era("%EY %EC %Ey"), Ok(~"0001 BC BC 1"));
+
+        assert_eq!(d.strftime_with_era("%EQ"), Err(format::UnknownSpecifier('Q')));
+        assert_eq!(d.strftime_with_era("%Y %Q"), Err(format::UnknownSpecifier('Q')));
+        assert_eq!(d.strftime_with_era("%Y %E"), Err(format::UnknownSpecifier('E')));
+    }
+
+    #[test]
+    fn to_str() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.to_str(), ~"2009-02-13 23:31:30");
+        assert_eq!(d.to_str(), d.iso_format());
+        assert_eq!(d.to_str(), d.get_cal().to_str());
+        assert_eq!(Date::from_timestamp_millis(-1).to_str(), ~"1969-12-31 23:59:59");
+    }
+
+    #[test]
+    fn copy_and_clone() {
+        fn time_of(d: Date) -> i64 { d.get_time() }
+        fn year_of(d: Date) -> uint { d.get_cal().get_year() }
+
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(time_of(d), 1234567890543);
+        assert_eq!(year_of(d), 2009);
+        assert_eq!(d.clone(), d);
+        assert_eq!(d.get_cal(), d.get_cal());
+    }
+
+    #[test]
+    fn from_parts_utc_and_local() {
+        let d = Date::from_parts_utc(2009, 2, 13, 23, 31, 30).unwrap();
+        assert_eq!(d.get_time(), 1234567890000);
+        assert_eq!(Date::from_parts_utc(2009, 2, 29, 0, 0, 0).unwrap_err(), InvalidDay);
+
+        let ist = FixedOffset::new(5, 30);
+        assert_eq!(Date::from_parts_local(2009, 2, 14, 5, 1, 30, &ist).unwrap(), d);
+        let nst = FixedOffset::new(-3, -30);
+        assert_eq!(Date::from_parts_local(2009, 2, 13, 20, 1, 30, &nst).unwrap(), d);
+        assert_eq!(Date::from_parts_local(2009, 2, 13, 23, 31, 30, &FixedOffset::utc())
+                   .unwrap(), d);
+        assert_eq!(Date::from_parts_local(2009, 2, 13, 24, 0, 0, &ist).unwrap_err(),
+                   InvalidHour);
+        assert_eq!(Date::from_parts_local(0, 1, 1, 0, 0, 0, &ist).unwrap_err(), InvalidYear);
+    }
+
+    #[test]
+    fn beginning_and_end() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.beginning_of_day().strftime("%F %T"), ~"2009-02-13 00:00:00");
+        assert_eq!(d.beginning_of_day().get_cal().get_msec(), 0);
+        assert_eq!(d.end_of_day().strftime("%F %T"), ~"2009-02-13 23:59:59");
+        assert_eq!(d.beginning_of_month().strftime("%F %T"), ~"2009-02-01 00:00:00");
+        assert_eq!(d.end_of_month().strftime("%F %T"), ~"2009-02-28 23:59:59");
+
+        let leap = Date::from_ymd(2008, 2, 10).unwrap();
+        assert_eq!(leap.end_of_month().strftime("%F"), ~"2008-02-29");
+        let d = Date::from_ymd(2009, 12, 31).unwrap();
+        assert_eq!(d.end_of_month().strftime("%F %T"), ~"2009-12-31 23:59:59");
+        assert_eq!(d.beginning_of_day(), d);
+
+        let d = Date::from_timestamp_millis(-1);
+        assert_eq!(d.beginning_of_day().strftime("%F %T"), ~"1969-12-31 00:00:00");
+        assert_eq!(d.beginning_of_month().strftime("%F %T"), ~"1969-12-01 00:00:00");
+    }
+
+    #[test]
+    fn leap_years() {
+        let year = |y: i32| Date::from_ymd(y, 6, 15).unwrap();
+        assert!(year(2000).is_leap_year());
+        assert!(!year(1900).is_leap_year());
+        assert!(year(2024).is_leap_year());
+        assert!(!year(2009).is_leap_year());
+        assert_eq!(year(2000).num_days_in_year(), 366);
+        assert_eq!(year(1900).num_days_in_year(), 365);
+        assert_eq!(year(2024).num_days_in_year(), 366);
+        assert_eq!(year(2009).num_days_in_year(), 365);
+    }
+
+    #[test]
+    fn unix_timestamp() {
+        let d = Date::from_unix_timestamp(1234567890).unwrap();
+        assert_eq!(d.get_time(), 1234567890000);
+        assert_eq!(d.unix_timestamp(), 1234567890);
+        assert_eq!(Date::from_timestamp_millis(1234567890543).unix_timestamp(), 1234567890);
+        assert_eq!(Date::from_unix_timestamp(-1).unwrap().strftime("%F %T"),
+                   ~"1969-12-31 23:59:59");
+        assert_eq!(Date::from_unix_timestamp(i64::max_value / 100), None);
+        assert_eq!(Date::from_unix_timestamp(-62167219201), None);
+        assert_eq!(Date::from_timestamp_millis(-500).unix_timestamp(), -1);
+
+        let d = Date::from_unix_timestamp_nanos(1234567890543210987);
+        assert_eq!(d.get_time(), 1234567890543);
+        assert_eq!(d.unix_timestamp_nanos(), Some(1234567890543000000));
+        assert_eq!(Date::from_unix_timestamp_nanos(-1).get_time(), -1);
+        assert_eq!(Date::from_ymd(2263, 1, 1).unwrap().unix_timestamp_nanos(), None);
+        assert_eq!(Date::from_ymd(1677, 1, 1).unwrap().unix_timestamp_nanos(), None);
+    }
+
+    #[test]
+    fn add_months() {
+        let ymd = |y: i32, m: u32, d: u32| Date::from_ymd(y, m, d).unwrap();
+        assert_eq!(ymd(2024, 1, 28).add_months(1), ymd(2024, 2, 28));
+        assert_eq!(ymd(2024, 1, 29).add_months(1), ymd(2024, 2, 29));
+        assert_eq!(ymd(2024, 1, 30).add_months(1), ymd(2024, 2, 29));
+        assert_eq!(ymd(2024, 1, 31).add_months(1), ymd(2024, 2, 29));
+        assert_eq!(ymd(2023, 1, 28).add_months(1), ymd(2023, 2, 28));
+        assert_eq!(ymd(2023, 1, 29).add_months(1), ymd(2023, 2, 28));
+        assert_eq!(ymd(2023, 1, 31).add_months(1), ymd(2023, 2, 28));
+        assert_eq!(ymd(2023, 3, 31).add_months(1), ymd(2023, 4, 30));
+
+        assert_eq!(ymd(2023, 12, 31).add_months(1), ymd(2024, 1, 31));
+        assert_eq!(ymd(2024, 1, 15).add_months(-1), ymd(2023, 12, 15));
+        assert_eq!(ymd(2024, 3, 31).add_months(-1), ymd(2024, 2, 29));
+        assert_eq!(ymd(2024, 5, 15).add_months(-17), ymd(2022, 12, 15));
+        assert_eq!(ymd(2024, 5, 15).add_months(0), ymd(2024, 5, 15));
+
+        let d = Date::from_timestamp_millis(1234567890543).add_months(1);
+        assert_eq!(d.strftime("%F %T"), ~"2009-03-13 23:31:30");
+        assert_eq!(d.get_cal().get_msec(), 543);
+        assert_eq!(d.weekday_name(), "Friday");
+        assert_eq!(d.get_cal().get_day_of_year(), 71);
+    }
+
+    #[test]
+    fn add_years() {
+        let ymd = |y: i32, m: u32, d: u32| Date::from_ymd(y, m, d).unwrap();
+        assert_eq!(ymd(2024, 2, 29).add_years(1), ymd(2025, 2, 28));
+        assert_eq!(ymd(2024, 2, 29).add_years(4), ymd(2028, 2, 29));
+        assert_eq!(ymd(2024, 2, 29).add_years(-1), ymd(2023, 2, 28));
+        assert_eq!(ymd(2024, 2, 29).add_years(0), ymd(2024, 2, 29));
+        assert_eq!(ymd(2009, 2, 13).add_years(-39), ymd(1970, 2, 13));
+
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.add_years(0), d);
+        assert_eq!(d.add_years(1).strftime("%F %T"), ~"2010-02-13 23:31:30");
+        for &n in [-3i32, -1, 1, 2, 10].iter() {
+            assert_eq!(d.add_years(n), d.add_months(n * 12));
+            assert_eq!(ymd(2023, 12, 31).add_years(n), ymd(2023, 12, 31).add_months(n * 12));
+        }
+    }
+
+    #[test]
+    fn weekend() {
+        let friday = Date::from_timestamp_millis(1234567890543);
+        let day = |n: i64| Date::from_timestamp_millis(friday.get_time() + n * 86400000);
+        let (saturday, sunday, monday) = (day(1), day(2), day(3));
+
+        assert!(friday.is_weekday() && !friday.is_weekend());
+        assert!(saturday.is_weekend() && !saturday.is_weekday());
+        assert!(sunday.is_weekend() && !sunday.is_weekday());
+        assert!(monday.is_weekday() && !monday.is_weekend());
+
+        assert_eq!(friday.next_weekday(), monday);
+        assert_eq!(saturday.next_weekday(), monday);
+        assert_eq!(sunday.next_weekday(), monday);
+        assert_eq!(monday.next_weekday(), day(4));
+        assert_eq!(monday.next_weekday().weekday_name(), "Tuesday");
+    }
+
+    #[test]
+    fn json() {
+        let d = Date::from_timestamp_millis(1234567890000);
+        let s = do io::with_str_writer |wr| {
+            let mut encoder = json::Encoder(wr);
+            d.encode(&mut encoder);
+        };
+        assert_eq!(s, ~"\"2009-02-13 23:31:30\"");
+        let mut decoder = json::Decoder(json::from_str(s).unwrap());
+        let back: Date = Decodable::decode(&mut decoder);
+        assert_eq!(back, d);
+
+        let d = Date::from_timestamp_millis(1234567890543);
+        let s = do io::with_str_writer |wr| {
+            let mut encoder = json::Encoder(wr);
+            EpochMillis(d).encode(&mut encoder);
+        };
+        assert_eq!(s, ~"1234567890543");
+        let mut decoder = json::Decoder(json::from_str(s).unwrap());
+        let EpochMillis(back) = Decodable::decode(&mut decoder);
+        assert_eq!(back, d);
+    }
+
+    #[test]
+    fn days_since() {
+        let feb28 = Date::from_ymd(2008, 2, 28).unwrap();
+        let mar1 = Date::from_ymd(2008, 3, 1).unwrap();
+        assert_eq!(mar1.days_since(&feb28), 2);
+        assert_eq!(feb28.days_since(&mar1), -2);
+        assert_eq!(feb28.days_until(&mar1), 2);
+
+        let before = Date::from_timestamp_millis(-3600000);
+        let after = Date::from_timestamp_millis(3600000);
+        assert_eq!(after.days_since(&before), 1);
+        assert_eq!(before.days_until(&after), 1);
+        assert_eq!(after.days_since_epoch(&before), 0);
+
+        let morning = Date::from_timestamp_millis(1234483200000);
+        let night = Date::from_timestamp_millis(1234569599999);
+        assert_eq!(night.days_since(&morning), 0);
+        assert_eq!(morning.days_until(&night), 0);
+    }
+
+    #[test]
+    fn week_of_year() {
+        let d = Date::from_ymd(2009, 1, 1).unwrap();
+        assert_eq!(d.iso_week_number(), 1);
+        assert_eq!(d.week_of_year(), 0);
+        assert_eq!(d.week_of_year_monday(), 0);
+
+        let d = Date::from_ymd(2008, 12, 28).unwrap();
+        assert_eq!(d.iso_week_number(), 52);
+        assert_eq!(d.week_of_year(), 52);
+        assert_eq!(d.week_of_year_monday(), 51);
+
+        let d = Date::from_ymd(2010, 1, 3).unwrap();
+        assert_eq!(d.iso_week_number(), 53);
+
+        for i in range(0i64, 400) {
+            let d = Date::from_timestamp_millis(1230681600000 + i * 86400000);
+            assert_eq!(format!("{:02u}", d.week_of_year()), d.strftime("%U"));
+            assert_eq!(format!("{:02u}", d.week_of_year_monday()), d.strftime("%W"));
+            assert_eq!(format!("{:02u}", d.iso_week_number()), d.strftime("%V"));
+        }
+    }
+
+    #[test]
+    fn strftime_checked() {
+        let d = Date::from_timestamp_millis(1234567890543);
+        assert_eq!(d.strftime_checked("%a, %d %b %Y %T %%"),
+                   Ok(~"Fri, 13 Feb 2009 23:31:30 %"));
+        assert_eq!(d.strftime_checked("%Y-%Q"), Err(format::UnknownSpecifier('Q')));
+        assert_eq!(d.strftime("%Y-%Q"), ~"2009-strftime: can't understand this format Q ");
+        assert_eq!(d.strftime("100%"), ~"100%");
+    }
+
+    #[test]
+    fn twelve_hour_clock() {
+        let midnight = Date::from_ymd_hms(2000, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(midnight.strftime("%I"), ~"12");
+        assert_eq!(midnight.strftime("%l"), ~"12");
+        assert_eq!(midnight.strftime("%p"), ~"AM");
+        assert_eq!(midnight.strftime("%r"), ~"12:00:00 AM");
+
+        let noon = Date::from_ymd_hms(2000, 1, 1, 12, 0, 0).unwrap();
+        assert_eq!(noon.strftime("%I %p"), ~"12 PM");
+        let one = Date::from_ymd_hms(2000, 1, 1, 13, 0, 0).unwrap();
+        assert_eq!(one.strftime("%I %p"), ~"01 PM");
+    }
+
+    #[test]
+    fn from_ordinal() {
+        let d = Date::from_ordinal(2009, 44).unwrap();
+        assert_eq!(d.strftime("%F"), ~"2009-02-13");
+        assert_eq!(Date::from_ordinal(2008, 366).unwrap().strftime("%F"), ~"2008-12-31");
+        assert_eq!(Date::from_ordinal(2009, 1).unwrap(), Date::from_ymd(2009, 1, 1).unwrap());
+
+        for &ms in [0i64, 1234483200000, 951782400000, 1230681600000, -86400000].iter() {
+            let d = Date::from_timestamp_millis(ms);
+            let cal = d.get_cal();
+            assert_eq!(Date::from_ordinal(cal.year as i32, (cal.yday + 1) as u32).unwrap(), d);
+        }
+
+        assert_eq!(Date::from_ordinal(2009, 0).unwrap_err(), InvalidDay);
+        assert_eq!(Date::from_ordinal(2009, 366).unwrap_err(), InvalidDay);
+        assert_eq!(Date::from_ordinal(-1, 1).unwrap_err(), InvalidYear);
+    }
+
+    #[test]
+    fn julian_day_number() {
+        assert_eq!(Date::unix_epoch().to_julian_day_number(), 2440588);
+        assert_eq!(Date::from_ymd(2000, 1, 1).unwrap().to_julian_day_number(), 2451545);
+        assert_eq!(Date::from_ymd_hms(1969, 7, 20, 20, 17, 40).unwrap().to_julian_day_number(),
+                   2440423);
+        assert_eq!(Date::from_ymd(0, 1, 1).unwrap().to_julian_day_number(), 1721060);
+
+        assert_eq!(Date::from_julian_day_number(2440588), Date::unix_epoch());
+        assert_eq!(Date::from_julian_day_number(2451545), Date::from_ymd(2000, 1, 1).unwrap());
+        assert_eq!(Date::from_julian_day_number(2440423).strftime("%F %T"),
+                   ~"1969-07-20 00:00:00");
+    }
+
+    #[test]
+    fn before_year_zero() {
+        let zero = Date::from_ymd(0, 1, 1).unwrap();
+        assert_eq!(Date::from_timestamp_millis_checked(zero.get_time()), Some(zero));
+        assert_eq!(Date::from_timestamp_millis_checked(zero.get_time() - 1), None);
+        assert_eq!(Date::from_timestamp_millis_checked(-1), Some(Date::from_timestamp_millis(-1)));
+
+        let day = Duration::from_days(1);
+        assert_eq!(zero.checked_sub(&day), None);
+        assert_eq!(zero.checked_add(&-day), None);
+        assert_eq!(zero.checked_add(&day), Some(zero + day));
+        assert_eq!(Date::unix_epoch().checked_sub(&day), Some(Date::unix_epoch() - day));
+        assert_eq!(Date::unix_epoch().checked_add(&Duration::from_millis(i64::max_value)), None);
+    }
+
+    #[test]
+    #[should_fail]
+    fn sub_before_year_zero() {
+        Date::from_ymd(0, 1, 1).unwrap() - Duration::from_days(1);
     }
 }
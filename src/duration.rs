@@ -0,0 +1,670 @@
+// Copyright 2013 Luis de Bethencourt <luis@debethencourt.com>
+// Copyright 2013 The Rust Project Developers
+// http://rust-lang.org
+
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * duration module for the datetime library of the Rust programming language
+ *
+ * A duration is a signed amount of time with millisecond precision.
+ */
+
+use std::from_str::FromStr;
+use std::num::{CheckedAdd, CheckedMul};
+use parse::{ParseError, InvalidFormat};
+
+static UNITS: [(&'static str, i64), ..4] = [
+    ("d", 86400000),
+    ("h", 3600000),
+    ("m", 60000),
+    ("s", 1000)
+];
+
+/*
+ * Names of the units read by parse_duration, in milliseconds. A year is
+ * 365.25 days and a month 30 days.
+ */
+static UNIT_NAMES: [(&'static str, i64), ..32] = [
+    ("ms", 1), ("msec", 1), ("msecs", 1), ("millisecond", 1), ("milliseconds", 1),
+    ("s", 1000), ("sec", 1000), ("secs", 1000), ("second", 1000), ("seconds", 1000),
+    ("m", 60000), ("min", 60000), ("mins", 60000), ("minute", 60000),
+    ("minutes", 60000),
+    ("h", 3600000), ("hr", 3600000), ("hrs", 3600000), ("hour", 3600000),
+    ("hours", 3600000),
+    ("d", 86400000), ("day", 86400000), ("days", 86400000),
+    ("w", 604800000), ("week", 604800000), ("weeks", 604800000),
+    ("mon", 2592000000), ("mons", 2592000000), ("month", 2592000000),
+    ("months", 2592000000),
+    ("year", 31557600000), ("years", 31557600000)
+];
+
+/*
+ * Units written by to_pg_interval, singular and plural, in milliseconds.
+ */
+static PG_UNITS: [(&'static str, &'static str, i64), ..3] = [
+    ("year", "years", 31557600000),
+    ("mon", "mons", 2592000000),
+    ("day", "days", 86400000)
+];
+
+#[deriving(Eq)]
+pub struct Duration {
+    /**
+    * Number of milliseconds, negative for durations going backwards.
+    */
+    priv millis: i64,
+}
+
+impl Duration {
+    pub fn from_millis(millis: i64) -> Duration {
+        Duration { millis: millis }
+    }
+
+    pub fn from_secs(secs: i64) -> Duration {
+        Duration { millis: secs * 1000 }
+    }
+
+    pub fn from_minutes(minutes: i64) -> Duration {
+        Duration { millis: minutes * 60000 }
+    }
+
+    pub fn from_hours(hours: i64) -> Duration {
+        Duration { millis: hours * 3600000 }
+    }
+
+    pub fn from_days(days: i64) -> Duration {
+        Duration { millis: days * 86400000 }
+    }
+
+    pub fn from_weeks(weeks: i64) -> Duration {
+        Duration { millis: weeks * 604800000 }
+    }
+
+    pub fn as_millis(&self) -> i64 {
+        self.millis
+    }
+
+    /**
+    * Returns the number of whole seconds, rounded toward zero. The as_*
+    * accessors below round the same way.
+    */
+    pub fn as_secs(&self) -> i64 {
+        self.millis / 1000
+    }
+
+    pub fn as_minutes(&self) -> i64 {
+        self.millis / 60000
+    }
+
+    pub fn as_hours(&self) -> i64 {
+        self.millis / 3600000
+    }
+
+    pub fn as_days(&self) -> i64 {
+        self.millis / 86400000
+    }
+
+    pub fn as_weeks(&self) -> i64 {
+        self.millis / 604800000
+    }
+
+    /**
+    * Returns the sum of both durations, or None if it overflows.
+    */
+    pub fn checked_add(&self, other: &Duration) -> Option<Duration> {
+        self.millis.checked_add(&other.millis).map(|ms| Duration::from_millis(ms))
+    }
+
+    /**
+    * Returns the duration multiplied by the given factor, or None if it
+    * overflows.
+    */
+    pub fn checked_mul(&self, factor: i64) -> Option<Duration> {
+        self.millis.checked_mul(&factor).map(|ms| Duration::from_millis(ms))
+    }
+}
+
+impl Add<Duration, Duration> for Duration {
+    fn add(&self, other: &Duration) -> Duration {
+        Duration::from_millis(self.millis + other.millis)
+    }
+}
+
+impl Sub<Duration, Duration> for Duration {
+    fn sub(&self, other: &Duration) -> Duration {
+        Duration::from_millis(self.millis - other.millis)
+    }
+}
+
+impl Neg<Duration> for Duration {
+    fn neg(&self) -> Duration {
+        Duration::from_millis(-self.millis)
+    }
+}
+
+impl Mul<i64, Duration> for Duration {
+    fn mul(&self, factor: &i64) -> Duration {
+        Duration::from_millis(self.millis * *factor)
+    }
+}
+
+impl Div<i64, Duration> for Duration {
+    fn div(&self, divisor: &i64) -> Duration {
+        Duration::from_millis(self.millis / *divisor)
+    }
+}
+
+impl TotalEq for Duration {
+    fn equals(&self, other: &Duration) -> bool {
+        self.millis == other.millis
+    }
+}
+
+impl Ord for Duration {
+    fn lt(&self, other: &Duration) -> bool {
+        self.millis < other.millis
+    }
+}
+
+impl TotalOrd for Duration {
+    fn cmp(&self, other: &Duration) -> Ordering {
+        self.millis.cmp(&other.millis)
+    }
+}
+
+/**
+ * Writes the duration with every non-zero unit, the way parse_duration
+ * reads it back: "1d2h30m15s250ms", "-90s", "0s".
+ */
+impl ToStr for Duration {
+    fn to_str(&self) -> ~str {
+        if self.millis == 0 {
+            return ~"0s";
+        }
+
+        let mut buf = if self.millis < 0 { ~"-" } else { ~"" };
+        let mut rest = self.millis.abs();
+        for &(name, size) in UNITS.iter() {
+            if rest >= size {
+                buf.push_str(format!("{}{}", rest / size, name));
+                rest %= size;
+            }
+        }
+        if rest > 0 {
+            buf.push_str(format!("{}ms", rest));
+        }
+        buf
+    }
+}
+
+pub struct CompactFormatConfig {
+    /*
+     * Options for format_duration_compact_with.
+     */
+    include_seconds: bool,   /* Whether to use the "s" unit       */
+    max_units: uint,         /* How many units to write, at most  */
+}
+
+impl CompactFormatConfig {
+    /**
+    * Returns the default configuration: seconds included, two units.
+    */
+    pub fn new() -> CompactFormatConfig {
+        CompactFormatConfig {
+            include_seconds: true,
+            max_units: 2
+        }
+    }
+}
+
+/**
+ * Formats the duration in as few characters as possible, using the default
+ * configuration.
+ *
+ * "3d2h", "2h15m", "45s", "<1s"
+ */
+pub fn format_duration_compact(duration: &Duration) -> ~str {
+    format_duration_compact_with(duration, &CompactFormatConfig::new())
+}
+
+/**
+ * Formats the duration in as few characters as possible. Starting at the
+ * largest non-zero unit, at most config.max_units units are considered and
+ * the ones that are zero are left out.
+ */
+pub fn format_duration_compact_with(duration: &Duration,
+                                    config: &CompactFormatConfig) -> ~str {
+    let units = if config.include_seconds { UNITS.slice(0, 4) } else { UNITS.slice(0, 3) };
+    let (smallest_name, smallest) = units[units.len() - 1];
+
+    let millis = duration.millis.abs();
+    if millis == 0 {
+        return ~"0" + smallest_name;
+    }
+    if millis < smallest {
+        return ~"<1" + smallest_name;
+    }
+
+    let mut buf = if duration.millis < 0 { ~"-" } else { ~"" };
+    let mut rest = millis;
+    let mut used = 0u;
+    for &(name, size) in units.iter() {
+        let count = rest / size;
+        rest %= size;
+        if used > 0 || count > 0 {
+            if used == config.max_units {
+                break;
+            }
+            if count > 0 {
+                buf.push_str(count.to_str() + name);
+            }
+            used += 1;
+        }
+    }
+
+    buf
+}
+
+impl FromStr for Duration {
+    fn from_str(s: &str) -> Option<Duration> {
+        match parse_duration(s) {
+            Ok(d) => Some(d),
+            Err(_) => None
+        }
+    }
+}
+
+/**
+ * Reads a duration written by a person, "2h30m", "2 hours and 30 minutes",
+ * "1 day, 6 hours", "90 seconds", or in ISO 8601, "P1DT6H". Unit names are
+ * not case sensitive and may be abbreviated ("h", "hr", "hour", "hours").
+ * Years are 365.25 days and months 30 days.
+ */
+pub fn parse_duration(s: &str) -> Result<Duration, ParseError> {
+    let s: ~str = s.iter().map(|c| {
+        if c >= 'A' && c <= 'Z' { (c as u8 + 32) as char } else { c }
+    }).collect();
+
+    if s.starts_with("p") {
+        parse_iso_duration(s.slice_from(1))
+    } else {
+        parse_natural_duration(s)
+    }
+}
+
+/**
+ * Formats the duration the way PostgreSQL writes an INTERVAL,
+ * "1 year 2 mons 3 days 04:05:06". Years are 365.25 days and months 30
+ * days, as in parse_duration, and every field of a negative duration
+ * carries the sign: "-3 days -04:00:00".
+ */
+pub fn to_pg_interval(duration: &Duration) -> ~str {
+    let negative = duration.millis < 0;
+    let sign = if negative { "-" } else { "" };
+    let mut rest = duration.millis.abs();
+    let mut fields = ~[];
+
+    for &(one, many, size) in PG_UNITS.iter() {
+        let n = rest / size;
+        rest %= size;
+        if n > 0 {
+            let name = if n == 1 && !negative { one } else { many };
+            fields.push(format!("{}{} {}", sign, n, name));
+        }
+    }
+
+    if rest > 0 || fields.is_empty() {
+        let mut time = format!("{}{:02d}:{:02d}:{:02d}", sign, rest / 3600000,
+                               rest / 60000 % 60, rest / 1000 % 60);
+        let ms = rest % 1000;
+        if ms > 0 {
+            let frac = format!(".{:03d}", ms);
+            time.push_str(frac.trim_right_chars(&'0'));
+        }
+        fields.push(time);
+    }
+
+    fields.connect(" ")
+}
+
+/**
+ * Reads a PostgreSQL INTERVAL in the postgres style,
+ * "1 year 2 mons 3 days 04:05:06", the verbose style,
+ * "@ 1 year 2 months 3 days 4 hours ago", or ISO 8601, "P1Y2M3DT4H".
+ * The SQL standard style, "1-2 3 4:05:06", is not read.
+ */
+pub fn from_pg_interval(s: &str) -> Result<Duration, ParseError> {
+    let s: ~str = s.trim().iter().map(|c| {
+        if c >= 'A' && c <= 'Z' { (c as u8 + 32) as char } else { c }
+    }).collect();
+    if s.starts_with("p") {
+        return parse_duration(s);
+    }
+
+    let words: ~[&str] = s.split_iter(' ').filter(|w| !w.is_empty()).collect();
+    let mut millis = 0i64;
+    let mut parts = 0u;
+    let mut i = 0u;
+
+    while i < words.len() {
+        let word = words[i];
+        i += 1;
+
+        if word == "@" && i == 1 {
+            continue;
+        }
+        if word == "ago" && i == words.len() && parts > 0 {
+            millis = -millis;
+            continue;
+        }
+        if word.contains_char(':') {
+            match read_pg_time(word) {
+                Some(ms) => millis += ms,
+                None => return Err(InvalidFormat)
+            }
+            parts += 1;
+            continue;
+        }
+
+        let n = match from_str::<i64>(word) {
+            Some(n) if i < words.len() => n,
+            _ => return Err(InvalidFormat)
+        };
+        let unit = match unit_millis(words[i]) {
+            Some(unit) => unit,
+            None => return Err(InvalidFormat)
+        };
+        i += 1;
+
+        millis += n * unit;
+        parts += 1;
+    }
+
+    if parts == 0 { Err(InvalidFormat) } else { Ok(Duration::from_millis(millis)) }
+}
+
+/*
+ * Reads a signed "HH:MM", "HH:MM:SS" or "HH:MM:SS.fff" time field, in
+ * milliseconds. Fraction digits after the third are dropped.
+ */
+fn read_pg_time(s: &str) -> Option<i64> {
+    let (negative, s) = if s.starts_with("-") { (true, s.slice_from(1)) } else { (false, s) };
+    let fields: ~[&str] = s.split_iter(':').collect();
+    if fields.len() < 2 || fields.len() > 3 {
+        return None;
+    }
+
+    let mut millis = 0i64;
+    for (i, &field) in fields.iter().enumerate() {
+        let (whole, frac) = match field.find('.') {
+            Some(dot) if i == 2 => (field.slice_to(dot), field.slice_from(dot + 1)),
+            _ => (field, "")
+        };
+        let mut pos = 0u;
+        let n = match read_count(whole, &mut pos) {
+            Some(n) if pos == whole.len() => n,
+            _ => return None
+        };
+        millis += n * [3600000, 60000, 1000][i];
+
+        let mut scale = 100;
+        for c in frac.iter() {
+            if c < '0' || c > '9' {
+                return None;
+            }
+            millis += (c as u8 - '0' as u8) as i64 * scale;
+            scale /= 10;
+        }
+    }
+
+    Some(if negative { -millis } else { millis })
+}
+
+/*
+ * Reads "<n> <unit>" pairs separated by spaces, commas or "and".
+ */
+fn parse_natural_duration(s: &str) -> Result<Duration, ParseError> {
+    let mut pos = 0u;
+    let mut millis = 0i64;
+    let mut parts = 0u;
+
+    loop {
+        loop {
+            if pos < s.len() && (s[pos] == ' ' as u8 || s[pos] == ',' as u8) {
+                pos += 1;
+            } else if s.slice_from(pos).starts_with("and ") {
+                pos += 4;
+            } else {
+                break;
+            }
+        }
+        if pos == s.len() {
+            break;
+        }
+
+        let n = match read_count(s, &mut pos) {
+            Some(n) => n,
+            None => return Err(InvalidFormat)
+        };
+        while pos < s.len() && s[pos] == ' ' as u8 {
+            pos += 1;
+        }
+        let start = pos;
+        while pos < s.len() && s[pos] >= 'a' as u8 && s[pos] <= 'z' as u8 {
+            pos += 1;
+        }
+        let unit = match unit_millis(s.slice(start, pos)) {
+            Some(unit) => unit,
+            None => return Err(InvalidFormat)
+        };
+
+        millis += n * unit;
+        parts += 1;
+    }
+
+    if parts == 0 { Err(InvalidFormat) } else { Ok(Duration::from_millis(millis)) }
+}
+
+/*
+ * Reads the part of an ISO 8601 duration after the "P": "1Y2M3W4DT5H6M7S".
+ */
+fn parse_iso_duration(s: &str) -> Result<Duration, ParseError> {
+    let mut pos = 0u;
+    let mut millis = 0i64;
+    let mut parts = 0u;
+    let mut time = false;
+
+    while pos < s.len() {
+        if !time && s[pos] == 't' as u8 {
+            time = true;
+            pos += 1;
+            if pos == s.len() {
+                return Err(InvalidFormat);
+            }
+            continue;
+        }
+
+        let n = match read_count(s, &mut pos) {
+            Some(n) => n,
+            None => return Err(InvalidFormat)
+        };
+        if pos == s.len() {
+            return Err(InvalidFormat);
+        }
+        let unit = match (time, s[pos] as char) {
+            (false, 'y') => 31557600000,
+            (false, 'm') => 2592000000,
+            (false, 'w') => 604800000,
+            (false, 'd') => 86400000,
+            (true, 'h') => 3600000,
+            (true, 'm') => 60000,
+            (true, 's') => 1000,
+            _ => return Err(InvalidFormat)
+        };
+        pos += 1;
+
+        millis += n * unit;
+        parts += 1;
+    }
+
+    if parts == 0 { Err(InvalidFormat) } else { Ok(Duration::from_millis(millis)) }
+}
+
+/*
+ * Reads a whole number.
+ */
+fn read_count(s: &str, pos: &mut uint) -> Option<i64> {
+    let start = *pos;
+    while *pos < s.len() && s[*pos] >= '0' as u8 && s[*pos] <= '9' as u8 {
+        *pos += 1;
+    }
+    if *pos == start { None } else { from_str::<i64>(s.slice(start, *pos)) }
+}
+
+fn unit_millis(name: &str) -> Option<i64> {
+    for &(unit, millis) in UNIT_NAMES.iter() {
+        if unit == name {
+            return Some(millis);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Duration, CompactFormatConfig, format_duration_compact,
+                format_duration_compact_with, parse_duration};
+    use super::{to_pg_interval, from_pg_interval};
+    use std::i64;
+    use parse::InvalidFormat;
+
+    #[test]
+    fn compact() {
+        let d = Duration::from_millis(((3 * 24 + 2) * 60 + 15) * 60000 + 45000);
+        assert_eq!(format_duration_compact(&d), ~"3d2h");
+        assert_eq!(format_duration_compact(&Duration::from_millis(8100000)), ~"2h15m");
+        assert_eq!(format_duration_compact(&Duration::from_millis(45000)), ~"45s");
+        assert_eq!(format_duration_compact(&Duration::from_millis(999)), ~"<1s");
+        assert_eq!(format_duration_compact(&Duration::from_millis(0)), ~"0s");
+        assert_eq!(format_duration_compact(&Duration::from_millis(-8100000)), ~"-2h15m");
+        /* Zero units inside the window are left out */
+        assert_eq!(format_duration_compact(&Duration::from_millis(3 * 86400000 + 60000)),
+                   ~"3d");
+
+        let mut config = CompactFormatConfig::new();
+        config.max_units = 4;
+        assert_eq!(format_duration_compact_with(&d, &config), ~"3d2h15m45s");
+        config.include_seconds = false;
+        assert_eq!(format_duration_compact_with(&d, &config), ~"3d2h15m");
+        assert_eq!(format_duration_compact_with(&Duration::from_millis(45000), &config),
+                   ~"<1m");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        let millis = |s: &str| parse_duration(s).unwrap().as_millis();
+        assert_eq!(from_str::<Duration>("2h30m").unwrap().as_millis(), 9000000);
+        assert_eq!(millis("2 hours 30 minutes"), 9000000);
+        assert_eq!(millis("2 Hours and 30 Minutes"), 9000000);
+        assert_eq!(millis("2hr 30min"), 9000000);
+        assert_eq!(millis("1 day, 6 hours"), 108000000);
+        assert_eq!(millis("90 seconds"), 90000);
+        assert_eq!(millis("1w"), 604800000);
+        assert_eq!(millis("250ms"), 250);
+
+        assert_eq!(millis("P1DT6H"), 108000000);
+        assert_eq!(millis("PT2H30M"), 9000000);
+        assert_eq!(millis("P1M"), 2592000000);
+        assert_eq!(millis("P1Y"), 31557600000);
+        assert_eq!(millis("pt90s"), 90000);
+
+        assert_eq!(parse_duration("").unwrap_err(), InvalidFormat);
+        assert_eq!(parse_duration("2 parsecs").unwrap_err(), InvalidFormat);
+        assert_eq!(parse_duration("hours").unwrap_err(), InvalidFormat);
+        assert_eq!(parse_duration("P").unwrap_err(), InvalidFormat);
+        assert_eq!(parse_duration("P1DT").unwrap_err(), InvalidFormat);
+        assert_eq!(parse_duration("P1H").unwrap_err(), InvalidFormat);
+        assert!(from_str::<Duration>("soon").is_none());
+    }
+
+    #[test]
+    fn constructors() {
+        assert_eq!(Duration::from_secs(90).as_millis(), 90000);
+        assert_eq!(Duration::from_minutes(90), Duration::from_secs(5400));
+        assert_eq!(Duration::from_hours(48), Duration::from_days(2));
+        assert_eq!(Duration::from_weeks(1), Duration::from_days(7));
+
+        let d = Duration::from_millis(2 * 604800000 + 3 * 86400000 + 5399999);
+        assert_eq!(d.as_weeks(), 2);
+        assert_eq!(d.as_days(), 17);
+        assert_eq!(d.as_hours(), 17 * 24 + 1);
+        assert_eq!(d.as_minutes(), (17 * 24 + 1) * 60 + 29);
+        assert_eq!(d.as_secs(), ((17 * 24 + 1) * 60 + 29) * 60 + 59);
+        assert_eq!(Duration::from_millis(-1999).as_secs(), -1);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let day = Duration::from_days(1);
+        let hour = Duration::from_hours(1);
+        assert_eq!(day + hour, Duration::from_hours(25));
+        assert_eq!(day - hour, Duration::from_hours(23));
+        assert_eq!(-hour, Duration::from_hours(-1));
+        assert_eq!(hour * 3, Duration::from_minutes(180));
+        assert_eq!(day / 4, Duration::from_hours(6));
+
+        assert!(hour < day);
+        assert!(-day < hour);
+        assert_eq!(day.cmp(&hour), Greater);
+        assert!(hour.equals(&Duration::from_minutes(60)));
+
+        assert_eq!(day.checked_add(&hour), Some(Duration::from_hours(25)));
+        assert_eq!(Duration::from_millis(i64::max_value).checked_add(&hour), None);
+        assert_eq!(hour.checked_mul(24), Some(day));
+        assert_eq!(Duration::from_millis(i64::max_value / 2).checked_mul(3), None);
+    }
+
+    #[test]
+    fn to_str() {
+        let d = Duration::from_millis(((26 * 60 + 30) * 60 + 15) * 1000 + 250);
+        assert_eq!(d.to_str(), ~"1d2h30m15s250ms");
+        assert_eq!(parse_duration(d.to_str()).unwrap(), d);
+        assert_eq!(Duration::from_secs(-90).to_str(), ~"-1m30s");
+        assert_eq!(Duration::from_millis(0).to_str(), ~"0s");
+        assert_eq!(Duration::from_hours(3).to_str(), ~"3h");
+    }
+
+    #[test]
+    fn pg_interval() {
+        let d = Duration::from_millis(31557600000 + 2 * 2592000000 + 3 * 86400000 + 4 * 3600000);
+        assert_eq!(to_pg_interval(&d), ~"1 year 2 mons 3 days 04:00:00");
+        assert_eq!(from_pg_interval(to_pg_interval(&d)).unwrap(), d);
+        assert_eq!(from_pg_interval("1 year 2 months 3 days 4 hours").unwrap(), d);
+        assert_eq!(from_pg_interval("@ 1 year 2 mons 3 days 4 hours").unwrap(), d);
+        assert_eq!(from_pg_interval("P1Y2M3DT4H").unwrap(), d);
+        assert_eq!(from_pg_interval("@ 1 year 2 mons 3 days 4 hours ago").unwrap(), -d);
+        assert_eq!(to_pg_interval(&-d), ~"-1 years -2 mons -3 days -04:00:00");
+        assert_eq!(from_pg_interval(to_pg_interval(&-d)).unwrap(), -d);
+
+        let d = Duration::from_millis(((4 * 60 + 5) * 60 + 6) * 1000 + 500);
+        assert_eq!(to_pg_interval(&d), ~"04:05:06.5");
+        assert_eq!(from_pg_interval("04:05:06.5").unwrap(), d);
+        assert_eq!(from_pg_interval("04:05:06.500123").unwrap(), d);
+        assert_eq!(from_pg_interval("P1Y2M3DT4H5M6S").unwrap().as_millis(),
+                   31557600000 + 2 * 2592000000 + 3 * 86400000 + 14706000);
+        assert_eq!(to_pg_interval(&Duration::from_days(1)), ~"1 day");
+        assert_eq!(to_pg_interval(&Duration::from_millis(0)), ~"00:00:00");
+        assert_eq!(from_pg_interval("00:00:00").unwrap(), Duration::from_millis(0));
+
+        assert_eq!(from_pg_interval("").unwrap_err(), InvalidFormat);
+        assert_eq!(from_pg_interval("3 fortnights").unwrap_err(), InvalidFormat);
+        assert_eq!(from_pg_interval("3").unwrap_err(), InvalidFormat);
+        assert_eq!(from_pg_interval("04:05:xx").unwrap_err(), InvalidFormat);
+        assert_eq!(from_pg_interval("1-2 3 4:05:06").unwrap_err(), InvalidFormat);
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright 2013 Luis de Bethencourt <luis@debethencourt.com>
+// Copyright 2013 The Rust Project Developers
+// http://rust-lang.org
+
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * format module for the datetime library of the Rust programming language
+ *
+ * Format strings are compiled once into a list of items, so the same
+ * format can be applied to many dates without reading it again.
+ */
+
+use gcalendar::GCalendar;
+
+#[deriving(Eq, Clone)]
+pub enum FormatItem {
+    Literal(~str),     /* Text copied as it is */
+    Specifier(char),   /* %-field              */
+}
+
+#[deriving(Eq, Clone)]
+pub enum FormatError {
+    UnexpectedSpecifier(char),   /* %-field not allowed in this format */
+    UnknownSpecifier(char),      /* %-field strftime does not know     */
+}
+
+pub struct CompiledFormat {
+    priv items: ~[FormatItem],
+}
+
+impl CompiledFormat {
+    /**
+    * Compiles the format string. A '%' at the very end is kept as text.
+    */
+    pub fn new(format: &str) -> CompiledFormat {
+        let mut items = ~[];
+        let mut literal = ~"";
+        let mut chars = format.iter();
+
+        loop {
+            match chars.next() {
+                None => break,
+                Some('%') => match chars.next() {
+                    Some(ch) => {
+                        if !literal.is_empty() {
+                            items.push(Literal(literal));
+                            literal = ~"";
+                        }
+                        items.push(Specifier(ch));
+                    }
+                    None => literal.push_char('%')
+                },
+                Some(ch) => literal.push_char(ch)
+            }
+        }
+
+        if !literal.is_empty() {
+            items.push(Literal(literal));
+        }
+        CompiledFormat { items: items }
+    }
+
+    /**
+    * Allocates a CompiledFormat object from already compiled items.
+    */
+    pub fn from_items(items: ~[FormatItem]) -> CompiledFormat {
+        CompiledFormat { items: items }
+    }
+
+    pub fn get_items<'a>(&'a self) -> &'a [FormatItem] {
+        self.items.as_slice()
+    }
+
+    /**
+    * Checks that the format has no field showing the date, so it can only
+    * show the time of day.
+    */
+    pub fn check_time_only(&self) -> Result<(), FormatError> {
+        self.check_specifiers(is_date_specifier)
+    }
+
+    /**
+    * Checks that the format has no field showing the time of day, so it can
+    * only show the date.
+    */
+    pub fn check_date_only(&self) -> Result<(), FormatError> {
+        self.check_specifiers(is_time_specifier)
+    }
+
+    /**
+    * Checks that strftime knows every field of the format.
+    */
+    pub fn check_known(&self) -> Result<(), FormatError> {
+        for item in self.items.iter() {
+            match *item {
+                Specifier(ch) if !is_known_specifier(ch) => return Err(UnknownSpecifier(ch)),
+                _ => ()
+            }
+        }
+        Ok(())
+    }
+
+    /* Fails on the first specifier matching the predicate */
+    fn check_specifiers(&self, forbidden: &fn(char) -> bool) -> Result<(), FormatError> {
+        for item in self.items.iter() {
+            match *item {
+                Specifier(ch) if forbidden(ch) => return Err(UnexpectedSpecifier(ch)),
+                _ => ()
+            }
+        }
+        Ok(())
+    }
+
+    /**
+    * Formats the calendar time according to the compiled format.
+    */
+    pub fn format(&self, cal: &GCalendar) -> ~str {
+        let mut buf = ~"";
+
+        for item in self.items.iter() {
+            match *item {
+                Literal(ref text) => buf.push_str(text.as_slice()),
+                Specifier(ch) => buf.push_str(cal.get_date(ch))
+            }
+        }
+
+        buf
+    }
+}
+
+/*
+ * Specifiers GCalendar::get_date understands.
+ */
+fn is_known_specifier(ch: char) -> bool {
+    match ch {
+        'A' | 'a' | 'B' | 'b' | 'C' | 'c' | 'D' | 'd' | 'e' | 'F' | 'f' | 'G' |
+        'g' | 'H' | 'h' | 'I' | 'j' | 'k' | 'l' | 'M' | 'm' | 'n' | 'P' | 'p' |
+        'R' | 'r' | 'S' | 's' | 'T' | 't' | 'U' | 'u' | 'V' | 'v' | 'W' | 'w' |
+        'X' | 'x' | 'Y' | 'y' | 'Z' | 'z' | '%' => true,
+        _ => false
+    }
+}
+
+/*
+ * Specifiers showing any part of the date. %c and %s show both date and time.
+ */
+fn is_date_specifier(ch: char) -> bool {
+    match ch {
+        'A' | 'a' | 'B' | 'b' | 'C' | 'c' | 'D' | 'd' | 'e' | 'F' | 'G' | 'g' |
+        'h' | 'j' | 'm' | 's' | 'U' | 'u' | 'V' | 'v' | 'W' | 'w' | 'Y' | 'y' => true,
+        _ => false
+    }
+}
+
+/*
+ * Specifiers showing any part of the time of day.
+ */
+fn is_time_specifier(ch: char) -> bool {
+    match ch {
+        'c' | 'f' | 'H' | 'I' | 'k' | 'l' | 'M' | 'P' | 'p' | 'R' | 'r' | 'S' |
+        's' | 'T' => true,
+        _ => false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CompiledFormat, Literal, Specifier, UnexpectedSpecifier, UnknownSpecifier};
+    use gcalendar::GCalendar;
+
+    #[test]
+    fn new() {
+        let f = CompiledFormat::new("%Y-%m-%d at %H%%");
+        assert_eq!(f.get_items(), &[Specifier('Y'), Literal(~"-"), Specifier('m'),
+                                    Literal(~"-"), Specifier('d'), Literal(~" at "),
+                                    Specifier('H'), Specifier('%')]);
+        assert_eq!(CompiledFormat::new("").get_items().len(), 0);
+        assert_eq!(CompiledFormat::new("100%").get_items(), &[Literal(~"100%")]);
+        let items = ~[Specifier('d'), Literal(~" AD")];
+        assert_eq!(CompiledFormat::from_items(items.clone()).get_items(), items.as_slice());
+    }
+
+    #[test]
+    fn format() {
+        let gc = GCalendar::from_epoch_millis(1234567890543);
+        let f = CompiledFormat::new("%a, %d %b %Y %T");
+        assert_eq!(f.format(&gc), ~"Fri, 13 Feb 2009 23:31:30");
+    }
+
+    #[test]
+    fn check_time_and_date_only() {
+        let time = CompiledFormat::new("%H:%M:%S.%f %z");
+        assert_eq!(time.check_time_only(), Ok(()));
+        assert_eq!(time.check_date_only(), Err(UnexpectedSpecifier('H')));
+
+        let date = CompiledFormat::new("%A %d %B %Y %%");
+        assert_eq!(date.check_date_only(), Ok(()));
+        assert_eq!(date.check_time_only(), Err(UnexpectedSpecifier('A')));
+
+        let both = CompiledFormat::new("%c");
+        assert_eq!(both.check_time_only(), Err(UnexpectedSpecifier('c')));
+        assert_eq!(both.check_date_only(), Err(UnexpectedSpecifier('c')));
+    }
+
+    #[test]
+    fn check_known() {
+        assert_eq!(CompiledFormat::new("%a, %d %b %Y %T %z %%").check_known(), Ok(()));
+        assert_eq!(CompiledFormat::new("%Y-%Q").check_known(), Err(UnknownSpecifier('Q')));
+        assert_eq!(CompiledFormat::new("100%").check_known(), Ok(()));
+    }
+}
@@ -19,16 +19,49 @@
  * http://en.wikipedia.org/wiki/Julian_calendar
  */
 
-static YEARBASE: int = 1900;
+use types::{Month, Weekday};
+
 static DAYSPERLYEAR: uint = 366;
 static DAYSPERNYEAR: uint = 365;
 static DAYSPERWEEK: uint = 7;
+static MACEPOCHOFFSET: f64 = 978307200.0;
+static J2000SECONDS: f64 = 946728000.0;
+static JULIANYEARSECONDS: f64 = 31557600.0;
 static DAYSBEFOREMONTH: [[uint, ..13], ..2] = [
     /* Normal years */
     [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334, 365],
     /* Leap years */
     [0, 31, 60, 91, 121, 152, 182, 213, 244, 274, 305, 335, 366]
 ];
+pub static MONTH_NAMES: [&'static str, ..12] = [
+    "January", "February", "March", "April", "May", "June", "July",
+    "August", "September", "October", "November", "December"
+];
+pub static MONTH_ABBREVS: [&'static str, ..12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun",
+    "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"
+];
+pub static DAY_NAMES: [&'static str, ..7] = [
+    "Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"
+];
+pub static DAY_ABBREVS: [&'static str, ..7] = [
+    "Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"
+];
+
+#[deriving(Eq)]
+pub enum DateError {
+    InvalidYear,          /* Year out of range             */
+    InvalidMonth,         /* Month outside [1-12]          */
+    InvalidDay,           /* Day not in the month          */
+    InvalidHour,          /* Hour outside [0-23]           */
+    InvalidMinute,        /* Minute outside [0-59]         */
+    InvalidSecond,        /* Second outside [0-60]         */
+    InvalidMillisecond,   /* Millisecond outside [0-999]   */
+    InvalidNanosecond,    /* Nanosecond outside [0-999999999] */
+    InvalidTimezone,      /* Timezone not in the table     */
+    InvalidWeekday,       /* Day of the week outside [0-6] */
+    InvalidYearDay,       /* Day not in the year           */
+}
 
 pub fn is_leap_year(year: uint) -> bool {
     (year % 4 == 0) && ((year % 100 != 0) || (year % 400 == 0))
@@ -38,18 +71,71 @@ pub fn year_size(year: uint) -> uint {
     if is_leap_year(year) { DAYSPERLYEAR } else { DAYSPERNYEAR }
 }
 
+/*
+ * Days from the 1st of January of year 0, a leap year, to the 1st of January
+ * of the given year.
+ */
+fn days_before_year(year: uint) -> uint {
+    year * DAYSPERNYEAR + (year + 3) / 4 - (year + 99) / 100 + (year + 399) / 400
+}
+
+/**
+ * Returns the day of the year [0-365] of the given month [1-12] and day of
+ * the month [1-31], or None if the date does not exist.
+ */
+pub fn day_of_year(year: uint, month: uint, mday: uint) -> Option<uint> {
+    if month < 1 || month > 12 || mday < 1 {
+        return None;
+    }
 
+    let ip = DAYSBEFOREMONTH[if is_leap_year(year) {1} else {0}];
+    let yday = ip[month - 1] + mday - 1;
+    if yday < ip[month] { Some(yday) } else { None }
+}
+
+/*
+ * Number of days [28-31] in the given month, which must be in [1-12].
+ */
+fn days_in_month(year: uint, month: uint) -> uint {
+    let ip = DAYSBEFOREMONTH[if is_leap_year(year) {1} else {0}];
+    ip[month] - ip[month - 1]
+}
+
+/**
+ * Returns the day of the week [0-6], Sunday being 0, of the given month
+ * [1-12] and day of the month [1-31], using Zeller's congruence.
+ *
+ * http://en.wikipedia.org/wiki/Zeller%27s_congruence
+ */
+pub fn day_of_week(year: uint, month: uint, mday: uint) -> uint {
+    /* January and February count as months 13 and 14 of the year before */
+    let (m, y) = if month < 3 {
+        (month as int + 12, year as int - 1)
+    } else {
+        (month as int, year as int)
+    };
+    let k = y.mod_floor(&100);
+    let j = y.div_floor(&100);
+
+    let h = (mday as int + 13 * (m + 1) / 5 + k + k / 4 + j.div_floor(&4) + 5 * j)
+        .mod_floor(&7);
+    /* h is 0 for Saturday */
+    ((h + 6) % 7) as uint
+}
+
+#[deriving(Clone)]
 pub struct GCalendar {
     /*
      * Calendar object with date and time.
      */
+    msec: uint,        /* Milliseconds  [0-999] */
     sec: uint,         /* Seconds       [0-59]  */
     min: uint,         /* Minutes       [0-59]  */
     hour: uint,        /* Hours         [0-23]  */
-    mday: uint,        /* Day           [0-30]  */
-    month: uint,       /* Month         [0-11]  */
-    year: uint,        /* Year - 1900           */
-    wday: uint,        /* Day of week   [0-6]   */
+    mday: uint,        /* Day           [1-31]  */
+    month: uint,       /* Month         [1-12]  */
+    year: uint,        /* Year, e.g. 2009       */
+    wday: uint,        /* Day of week   [0-6], Sunday being 0 */
     yday: uint         /* Days in year  [0-365] */
 }
 
@@ -59,23 +145,27 @@ impl GCalendar {
     */
     pub fn new_at_epoch() -> GCalendar {
         GCalendar {
+            msec: 0,
             sec: 0,
             min: 0,
             hour: 0,
-            mday: 0,
-            month: 0,
-            year: 0,
-            wday: 0,
+            mday: 1,
+            month: 1,
+            year: 1970,
+            wday: 4,
             yday: 0,
         }
     }
 
     /**
-    * Allocates a GCalendar object at the given date and time.
+    * Allocates a GCalendar object at the given date and time. The month
+    * [1-12] and the day of the month [1-31] count from 1, the day of the
+    * week [0-6] and the day of the year [0-365] from 0.
     */
     pub fn new(sec: uint, min: uint, hour: uint, mday: uint, month: uint,
             year: uint, wday: uint, yday: uint) -> GCalendar {
         GCalendar {
+            msec: 0,
             sec: sec,
             min: min,
             hour: hour,
@@ -88,28 +178,156 @@ impl GCalendar {
     }
 
     /**
-    * Allocates a GCalendar object from the milliseconds elapsed since epoch.
+    * Like new, but fails with the error of the first field out of its
+    * range, see validate.
     */
-    pub fn new_from_epoch(since_epoch: uint) -> GCalendar {
+    pub fn new_checked(sec: uint, min: uint, hour: uint, mday: uint, month: uint,
+                       year: uint, wday: uint, yday: uint) -> Result<GCalendar, DateError> {
+        let cal = GCalendar::new(sec, min, hour, mday, month, year, wday, yday);
+        match cal.validate() {
+            Ok(()) => Ok(cal),
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+    * Checks that every field is in its range, the day of the month in the
+    * days of the month and the day of the year in the days of the year. It
+    * does not check that the days of the week and of the year match the
+    * date.
+    */
+    pub fn validate(&self) -> Result<(), DateError> {
+        if self.month < 1 || self.month > 12 {
+            return Err(InvalidMonth);
+        }
+        if self.mday < 1 || self.mday > days_in_month(self.year, self.month) {
+            return Err(InvalidDay);
+        }
+        if self.hour > 23 {
+            return Err(InvalidHour);
+        }
+        if self.min > 59 {
+            return Err(InvalidMinute);
+        }
+        if self.sec > 60 {
+            return Err(InvalidSecond);
+        }
+        if self.msec > 999 {
+            return Err(InvalidMillisecond);
+        }
+        if self.wday > 6 {
+            return Err(InvalidWeekday);
+        }
+        if self.yday >= year_size(self.year) {
+            return Err(InvalidYearDay);
+        }
+        Ok(())
+    }
+
+    /**
+    * Allocates a GCalendar object from fields that may be out of their
+    * range, rolling them over into the next field the way mktime does:
+    * month 13 is January of the next year, hour 25 is 1am of the next day
+    * and day 0 is the last day of the previous month.
+    */
+    pub fn new_from_parts_normalized(year: int, month: int, day: int, hour: int,
+                                     min: int, sec: int) -> GCalendar {
+        let min = min + sec.div_floor(&60);
+        let sec = sec.mod_floor(&60);
+        let hour = hour + min.div_floor(&60);
+        let min = min.mod_floor(&60);
+        let mut day = day + hour.div_floor(&24);
+        let hour = hour.mod_floor(&24);
+
+        let mut year = year + (month - 1).div_floor(&12);
+        let mut month = (month - 1).mod_floor(&12) + 1;
+
+        while day < 1 {
+            month -= 1;
+            if month < 1 {
+                month = 12;
+                year -= 1;
+            }
+            day += days_in_month(year as uint, month as uint) as int;
+        }
+        loop {
+            let size = days_in_month(year as uint, month as uint) as int;
+            if day <= size {
+                break;
+            }
+            day -= size;
+            month += 1;
+            if month > 12 {
+                month = 1;
+                year += 1;
+            }
+        }
+        assert!(year >= 0);
+
+        let (year, month, mday) = (year as uint, month as uint, day as uint);
+        GCalendar::new(sec as uint, min as uint, hour as uint, mday, month, year,
+                       day_of_week(year, month, mday),
+                       day_of_year(year, month, mday).unwrap())
+    }
+
+    #[deprecated="use `from_epoch_millis` instead"]
+    pub fn new_from_epoch(since_epoch: i64) -> GCalendar {
+        GCalendar::from_epoch_millis(since_epoch)
+    }
+
+    /**
+    * Allocates a GCalendar object at midnight of the given day of the year
+    * [1-366].
+    */
+    pub fn from_ordinal_date(year: uint, yday: uint)
+                             -> Result<GCalendar, DateError> {
+        if yday < 1 || yday > year_size(year) {
+            return Err(InvalidDay);
+        }
+
+        let ip = DAYSBEFOREMONTH[if is_leap_year(year) {1} else {0}];
+        let mut month = 11;
+        while (yday - 1 < ip[month]) {
+            month -= 1;
+        }
+        let mday = yday - ip[month];
+
+        Ok(GCalendar::new(0, 0, 0, mday, month + 1, year,
+                          day_of_week(year, month + 1, mday), yday - 1))
+    }
+
+    /**
+    * Allocates a GCalendar object from the milliseconds elapsed since epoch,
+    * negative for times before it. Times before year 0 are not supported.
+    */
+    pub fn from_epoch_millis(since_epoch: i64) -> GCalendar {
         let epoch_year = 1970;
-        let mut year = epoch_year;
 
         let millisecs_day = 86400000;
 
-        let mut dayclock = since_epoch % millisecs_day;
-        let mut dayno = since_epoch / millisecs_day;
+        let mut dayclock = since_epoch.mod_floor(&millisecs_day) as uint;
+        let days = since_epoch.div_floor(&millisecs_day);
 
         let hour = dayclock / 3600000;
         dayclock = dayclock - (hour * 3600000);
         let min = dayclock / 60000;
         dayclock = dayclock - (min * 60000);
         let sec = dayclock / 1000;
-        let wday = (dayno + 4) % 7;
+        let msec = dayclock - (sec * 1000);
+        let wday = (days + 4).mod_floor(&7) as uint;
 
-        while (dayno >= year_size(year)) {
-            dayno -= year_size(year);
+        let days = days + days_before_year(epoch_year) as i64;
+        assert!(days >= 0);
+        let mut dayno = days as uint;
+
+        /* 400 years are 146097 days, which gives the year off by one at most */
+        let mut year = dayno * 400 / 146097;
+        if days_before_year(year) > dayno {
+            year -= 1;
+        } else if days_before_year(year + 1) <= dayno {
             year += 1;
         }
+        dayno -= days_before_year(year);
         let yday = dayno;
 
         let ip = DAYSBEFOREMONTH[if is_leap_year(year) {1} else {0}];
@@ -120,6 +338,7 @@ impl GCalendar {
         dayno -= ip[month];
 
         GCalendar {
+            msec: msec,
             sec: sec,
             min: min,
             hour: hour,
@@ -131,6 +350,37 @@ impl GCalendar {
         }
     }
 
+    /**
+    * Allocates a GCalendar object from Mac absolute time: seconds since
+    * 2001-01-01 00:00:00 UTC, as used by NSDate and Core Data.
+    */
+    pub fn from_mac_absolute_time(secs: f64) -> GCalendar {
+        let since_epoch = secs + MACEPOCHOFFSET;
+        GCalendar::from_epoch_millis((since_epoch * 1000.0).round() as i64)
+    }
+
+    /**
+    * Allocates a GCalendar object from an astronomical Julian year, which
+    * has exactly 365.25 days and is counted from J2000.0, the 1st of January
+    * 2000 at 12:00.
+    */
+    pub fn from_julian_year(jy: f64) -> GCalendar {
+        let since_epoch = J2000SECONDS + (jy - 2000.0) * JULIANYEARSECONDS;
+        GCalendar::from_epoch_millis((since_epoch * 1000.0).round() as i64)
+    }
+
+    /**
+    * Returns the hour, minute and second of a number of seconds since
+    * midnight.
+    */
+    pub fn from_seconds_since_midnight(secs: uint) -> (uint, uint, uint) {
+        (secs / 3600, secs % 3600 / 60, secs % 60)
+    }
+
+    pub fn get_msec(&self) -> uint {
+        self.msec
+    }
+
     pub fn get_sec(&self) -> uint {
         self.sec
     }
@@ -163,28 +413,210 @@ impl GCalendar {
         self.yday
     }
 
+    /**
+    * Returns the number of days [28-31] in the given month [1-12] of the
+    * year. Fails for months out of range.
+    */
+    pub fn days_in_month(year: u32, month: u32) -> u32 {
+        if month < 1 || month > 12 {
+            fail!(format!("month {} outside [1-12]", month));
+        }
+        days_in_month(year as uint, month as uint) as u32
+    }
+
+    /**
+    * Returns the number of days [28-31] in the month of the calendar.
+    */
+    pub fn days_in_current_month(&self) -> u32 {
+        days_in_month(self.year, self.month) as u32
+    }
+
+    /**
+    * Returns the month as a Month.
+    */
+    pub fn month_enum(&self) -> Month {
+        Month::from_number(self.month as u32).unwrap()
+    }
+
+    /**
+    * Returns the day of the week as a Weekday.
+    */
+    pub fn weekday_enum(&self) -> Weekday {
+        Weekday::from_number_from_sunday(self.wday as u32).unwrap()
+    }
+
+    /**
+    * Returns (hour, min, sec, msec).
+    */
+    pub fn time_components(&self) -> (uint, uint, uint, uint) {
+        (self.hour, self.min, self.sec, self.msec)
+    }
+
+    /**
+    * Returns (year, month, mday).
+    */
+    pub fn date_components(&self) -> (uint, uint, uint) {
+        (self.year, self.month, self.mday)
+    }
+
+    /**
+    * Returns (year, month, mday, hour, min, sec, msec, wday, yday).
+    */
+    pub fn all_components(&self) -> (uint, uint, uint, uint, uint, uint, uint, uint, uint) {
+        (self.year, self.month, self.mday, self.hour, self.min, self.sec, self.msec,
+         self.wday, self.yday)
+    }
+
+    /**
+    * Returns the calendar packed in 16 bytes, little-endian: year as i16,
+    * month, mday, hour, min, sec and wday as u8, yday and msec as u16, and
+    * 4 bytes of zero padding. Years above 32767 do not fit.
+    */
+    pub fn to_packed_bytes(&self) -> [u8, ..16] {
+        assert!(self.year <= 32767);
+        let mut bytes = [0u8, ..16];
+        bytes[0] = self.year as u8;
+        bytes[1] = (self.year >> 8) as u8;
+        bytes[2] = self.month as u8;
+        bytes[3] = self.mday as u8;
+        bytes[4] = self.hour as u8;
+        bytes[5] = self.min as u8;
+        bytes[6] = self.sec as u8;
+        bytes[7] = self.wday as u8;
+        bytes[8] = self.yday as u8;
+        bytes[9] = (self.yday >> 8) as u8;
+        bytes[10] = self.msec as u8;
+        bytes[11] = (self.msec >> 8) as u8;
+        bytes
+    }
+
+    /**
+    * Allocates a GCalendar object from the 16 bytes of to_packed_bytes,
+    * checking every field. The day of the week and of the year must match
+    * the date, or InvalidDay is returned. The padding is ignored.
+    */
+    pub fn from_packed_bytes(bytes: &[u8, ..16]) -> Result<GCalendar, DateError> {
+        let year = (bytes[0] as u16 | bytes[1] as u16 << 8) as i16;
+        if year < 0 {
+            return Err(InvalidYear);
+        }
+        let (year, month, mday) = (year as uint, bytes[2] as uint, bytes[3] as uint);
+        if month < 1 || month > 12 {
+            return Err(InvalidMonth);
+        }
+        let yday = match day_of_year(year, month, mday) {
+            Some(yday) => yday,
+            None => return Err(InvalidDay)
+        };
+        if bytes[4] > 23 {
+            return Err(InvalidHour);
+        }
+        if bytes[5] > 59 {
+            return Err(InvalidMinute);
+        }
+        if bytes[6] > 60 {
+            return Err(InvalidSecond);
+        }
+        if bytes[7] as uint != day_of_week(year, month, mday) ||
+           (bytes[8] as uint | bytes[9] as uint << 8) != yday {
+            return Err(InvalidDay);
+        }
+        let msec = bytes[10] as uint | bytes[11] as uint << 8;
+        if msec > 999 {
+            return Err(InvalidMillisecond);
+        }
+
+        Ok(GCalendar {
+            msec: msec,
+            sec: bytes[6] as uint,
+            min: bytes[5] as uint,
+            hour: bytes[4] as uint,
+            mday: mday,
+            month: month,
+            year: year,
+            wday: bytes[7] as uint,
+            yday: yday,
+        })
+    }
+
+    /**
+    * Returns the full English name of the day of the week, as %A.
+    */
+    pub fn day_name(&self) -> &'static str {
+        DAY_NAMES[self.wday]
+    }
+
+    /**
+    * Returns the three letter abbreviation of the day of the week, as %a.
+    */
+    pub fn day_name_short(&self) -> &'static str {
+        DAY_ABBREVS[self.wday]
+    }
+
+    /**
+    * Returns the full English name of the month, as %B.
+    */
+    pub fn month_name(&self) -> &'static str {
+        MONTH_NAMES[self.month - 1]
+    }
+
+    /**
+    * Returns the three letter abbreviation of the month, as %b.
+    */
+    pub fn month_name_short(&self) -> &'static str {
+        MONTH_ABBREVS[self.month - 1]
+    }
+
+    /**
+    * Returns the century of the year, counted the historical way: the 20th
+    * century goes from 1901 to 2000 and the 21st starts in 2001.
+    *
+    * This is not the %C strftime field, which is the year divided by 100.
+    */
+    pub fn century(&self) -> uint {
+        (self.year + 99) / 100
+    }
+
+    /**
+    * Returns the number of seconds elapsed since midnight [0-86399].
+    */
+    pub fn seconds_since_midnight(&self) -> uint {
+        self.hour * 3600 + self.min * 60 + self.sec
+    }
+
+    /**
+    * Returns the number of milliseconds elapsed since midnight.
+    */
+    pub fn millis_since_midnight(&self) -> uint {
+        self.seconds_since_midnight() * 1000 + self.msec
+    }
+
     pub fn ydhms_diff(&self, year1: uint, yday1: uint, hour1: uint, min1: uint,
                       sec1: uint, year0: uint, yday0: uint, hour0: uint,
-                      min0: uint, sec0: uint) -> uint {
+                      min0: uint, sec0: uint) -> i64 {
         /* Return an integer value measuring (YEAR1-YDAY1 HOUR1:MIN1:SEC1) -
-        * (YEAR0-YDAY0 HOUR0:MIN0:SEC0) in seconds.
+        * (YEAR0-YDAY0 HOUR0:MIN0:SEC0) in seconds, negative if the first
+        * time is the earlier one.
         */
         // FIXME: Optimize way to calculate intervening leap days
-        let mut intervening_leap_days: uint = 0;
-        let mut y: uint = year1;
-        while (y > year0) {
+        let mut intervening_leap_days: i64 = 0;
+        let (mut y, last) = if year0 < year1 { (year0, year1) } else { (year1, year0) };
+        while (y < last) {
             if is_leap_year(y) {intervening_leap_days += 1;}
-            y -= 1;
+            y += 1;
+        }
+        if year1 < year0 {
+            intervening_leap_days = -intervening_leap_days;
         }
 
-        let years = (year1 - year0);
-        let days = 365 * years + yday1 - yday0 + intervening_leap_days;
-        let hours = 24 * days + hour1 - hour0;
-        let minutes = 60 * hours + min1 - min0;
-        60 * minutes + sec1 - sec0
+        let years = year1 as i64 - year0 as i64;
+        let days = 365 * years + yday1 as i64 - yday0 as i64 + intervening_leap_days;
+        let hours = 24 * days + hour1 as i64 - hour0 as i64;
+        let minutes = 60 * hours + min1 as i64 - min0 as i64;
+        60 * minutes + sec1 as i64 - sec0 as i64
     }
 
-    pub fn mktime(&self) -> uint {
+    pub fn mktime(&self) -> i64 {
         /* Convert a broken down time structure to a simple representation:
         * seconds since Epoch.
         */
@@ -192,14 +624,109 @@ impl GCalendar {
                         1970, 0, 0, 0, 0)
     }
 
-    pub fn iso_week_days (&self, yday: uint, wday: uint) -> int {
+    /**
+    * Returns the seconds elapsed since 0001-01-01 00:00:00 of the proleptic
+    * Gregorian calendar, the Rata Die day count times 86400 plus the
+    * seconds of the day. Calendar times sort the same way as this number.
+    */
+    pub fn total_seconds_since_year_zero(&self) -> i64 {
+        self.ydhms_diff(self.year, self.yday, self.hour, self.min, self.sec,
+                        1, 0, 0, 0, 0)
+    }
+
+    /**
+    * Moves the calendar time by a number of seconds, in place, rolling the
+    * other fields over as needed.
+    */
+    pub fn add_seconds(&mut self, secs: i64) {
+        let msec = self.msec;
+        *self = GCalendar::new_from_parts_normalized(self.year as int, self.month as int,
+                                                     self.mday as int, self.hour as int,
+                                                     self.min as int,
+                                                     self.sec as int + secs as int);
+        self.msec = msec;
+    }
+
+    /**
+    * Moves the calendar time by a number of minutes, in place, rolling the
+    * other fields over as needed.
+    */
+    pub fn add_minutes(&mut self, mins: i64) {
+        self.add_seconds(mins * 60);
+    }
+
+    /**
+    * Returns the seconds since 2001-01-01 00:00:00 UTC (Mac absolute time).
+    */
+    pub fn to_mac_absolute_time(&self) -> f64 {
+        self.mktime() as f64 + self.msec as f64 / 1000.0 - MACEPOCHOFFSET
+    }
+
+    /**
+    * Returns the astronomical Julian year: J2000.0 is the 1st of January
+    * 2000 at 12:00, and each year has exactly 365.25 days.
+    */
+    pub fn to_julian_year(&self) -> f64 {
+        let since_epoch = self.mktime() as f64 + self.msec as f64 / 1000.0;
+        2000.0 + (since_epoch - J2000SECONDS) / JULIANYEARSECONDS
+    }
+
+    /**
+    * Returns a compact timestamp with milliseconds for log messages.
+    *
+    * "2009-02-13 23:31:30.030"
+    */
+    pub fn format_for_log(&self) -> ~str {
+        format!("{}-{:02u}-{:02u} {:02u}:{:02u}:{:02u}.{:03u}",
+                self.year, self.month, self.mday, self.hour, self.min, self.sec, self.msec)
+    }
+
+    /**
+    * Returns format_for_log followed by the given offset, in seconds east of
+    * UTC, that the fields are in.
+    *
+    * "2009-02-14 05:01:30.030 +0530"
+    */
+    pub fn format_for_log_with_tz(&self, offset: int) -> ~str {
+        let sign = if offset < 0 { '-' } else { '+' };
+        let offset = offset.abs();
+        format!("{} {}{:02d}{:02d}", self.format_for_log(), sign,
+                offset / 3600, offset % 3600 / 60)
+    }
+
+    /**
+    * Returns the time of day on a 12-hour clock, "11:31:30 PM". Midnight is
+    * "12:00:00 AM" and noon "12:00:00 PM".
+    */
+    pub fn format_time_12h(&self) -> ~str {
+        let hour = match self.hour % 12 { 0 => 12, h => h };
+        let suffix = if self.hour < 12 { "AM" } else { "PM" };
+        format!("{:02u}:{:02u}:{:02u} {}", hour, self.min, self.sec, suffix)
+    }
+
+    /**
+    * Returns the time of day on a 24-hour clock, "23:31:30".
+    */
+    pub fn format_time_24h(&self) -> ~str {
+        format!("{:02u}:{:02u}:{:02u}", self.hour, self.min, self.sec)
+    }
+
+    /**
+    * Returns the time of day on a 24-hour clock with milliseconds,
+    * "23:31:30.030".
+    */
+    pub fn format_time_24h_with_ms(&self) -> ~str {
+        format!("{:02u}:{:02u}:{:02u}.{:03u}", self.hour, self.min, self.sec, self.msec)
+    }
+
+    pub fn iso_week_days (&self, yday: int, wday: uint) -> int {
         /* The number of days from the first day of the first ISO week of this
         * year to the year day YDAY with week day WDAY.
         * ISO weeks start on Monday. The first ISO week has the year's first
         * Thursday.
-        * YDAY may be as small as yday_minimum.
+        * YDAY may be as small as -yday_minimum, counting back into the
+        * previous year.
         */
-        let yday: int = yday as int;
         let wday: int = wday as int;
         let iso_week_start_wday: int = 1; /* Monday */
         let iso_week1_wday: int = 4;      /* Thursday */
@@ -211,17 +738,22 @@ impl GCalendar {
             + iso_week1_wday - iso_week_start_wday
      }
 
-    pub fn iso_week (&self, ch: char) -> ~str {
+    /**
+    * Returns the ISO 8601 week-based year and week number [1-53], which
+    * around New Year may belong to the previous or the next year.
+    */
+    pub fn iso_year_week(&self) -> (uint, uint) {
         let mut year: uint = self.year;
-        let mut days: int = self.iso_week_days (self.yday, self.wday);
+        let yday: int = self.yday as int;
+        let mut days: int = self.iso_week_days (yday, self.wday);
 
         if (days < 0) {
             /* This ISO week belongs to the previous year. */
             year -= 1;
-            days = self.iso_week_days (self.yday + (year_size(year)),
+            days = self.iso_week_days (yday + year_size(year) as int,
                                        self.wday);
         } else {
-            let d: int = self.iso_week_days (self.yday - (year_size(year)),
+            let d: int = self.iso_week_days (yday - year_size(year) as int,
                                              self.wday);
             if (0 <= d) {
                 /* This ISO week belongs to the next year. */
@@ -230,10 +762,16 @@ impl GCalendar {
             }
         }
 
+        (year, (days / 7 + 1) as uint)
+    }
+
+    pub fn iso_week (&self, ch: char) -> ~str {
+        let (year, week) = self.iso_year_week();
+
         match ch {
             'G' => format!("{}", year),
             'g' => format!("{:02u}", (year % 100 + 100) % 100),
-            'V' => format!("{:02d}", days / 7 + 1),
+            'V' => format!("{:02u}", week),
             _ => ~""
         }
     }
@@ -308,7 +846,7 @@ impl GCalendar {
             }
             'd' => format!("{:02u}", self.mday),
             'e' => format!("{:2u}", self.mday),
-            'f' => format!("{:09u}", self.sec),
+            'f' => format!("{:09u}", self.msec * 1000000),
             'F' => {
                 format!("{}-{}-{}",
                      self.get_date('Y'),
@@ -320,6 +858,7 @@ impl GCalendar {
             'H' => format!("{:02u}", self.hour),
             'I' => {
                 let mut h = self.hour;
+                if h == 0 { h = 12 }
                 if h > 12 { h -= 12 }
                 format!("{:02u}", h)
             }
@@ -374,16 +913,132 @@ impl GCalendar {
             'Y' => self.year.to_str(),
             'y' => format!("{:02u}", self.year % 100),
             'Z' => ~"UTC",
-            'z' => ~"-0000",
+            'z' => ~"+0000",
             '%' => ~"%",
             _   => die()
         }
     }
+
+    /* Fields in chronological order, leaving out the derived wday and yday */
+    fn key(&self) -> (uint, uint, uint, uint, uint, uint, uint) {
+        (self.year, self.month, self.mday, self.hour, self.min, self.sec, self.msec)
+    }
+}
+
+impl Eq for GCalendar {
+    fn eq(&self, other: &GCalendar) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl TotalEq for GCalendar {
+    fn equals(&self, other: &GCalendar) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Ord for GCalendar {
+    fn lt(&self, other: &GCalendar) -> bool {
+        self.key() < other.key()
+    }
+}
+
+impl TotalOrd for GCalendar {
+    fn cmp(&self, other: &GCalendar) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/**
+ * The ISO 8601 form of the calendar time, as in Date::iso_format.
+ */
+impl ToStr for GCalendar {
+    fn to_str(&self) -> ~str {
+        format!("{}-{:02u}-{:02u} {:02u}:{:02u}:{:02u}",
+                self.year, self.month, self.mday, self.hour, self.min, self.sec)
+    }
+}
+
+/**
+ * Calendar fields with readable names, to serialize a calendar time as an
+ * object such as {"year":2009,"month":2,"day":13,...}.
+ */
+#[deriving(Eq, Clone, Encodable, Decodable)]
+pub struct GCalendarJson {
+    year: uint,
+    month: uint,       /* [1-12] */
+    day: uint,         /* [1-31] */
+    hour: uint,
+    minute: uint,
+    second: uint,
+}
+
+impl GCalendarJson {
+    pub fn from_calendar(cal: &GCalendar) -> GCalendarJson {
+        GCalendarJson {
+            year: cal.year,
+            month: cal.month,
+            day: cal.mday,
+            hour: cal.hour,
+            minute: cal.min,
+            second: cal.sec
+        }
+    }
+
+    /**
+    * Returns the calendar time, checking that the fields make a valid date
+    * and time.
+    */
+    pub fn to_calendar(&self) -> Result<GCalendar, DateError> {
+        let yday = match day_of_year(self.year, self.month, self.day) {
+            Some(yday) => yday,
+            None => return Err(if self.month < 1 || self.month > 12 { InvalidMonth }
+                               else { InvalidDay })
+        };
+        if self.hour > 23 {
+            return Err(InvalidHour);
+        }
+        if self.minute > 59 {
+            return Err(InvalidMinute);
+        }
+        if self.second > 60 {
+            return Err(InvalidSecond);
+        }
+
+        Ok(GCalendar::new(self.second, self.minute, self.hour, self.day, self.month,
+                          self.year, day_of_week(self.year, self.month, self.day), yday))
+    }
+}
+
+/*
+ * Deterministic pseudo random milliseconds since epoch, from 1800 up to 2200,
+ * for the tests of this and other modules.
+ */
+#[cfg(test)]
+pub fn random_epochs(n: uint) -> ~[i64] {
+    let mut x = 0x2545F4914F6CDD1Du64;
+    let mut epochs = ~[];
+    for _ in range(0, n) {
+        x = x * 6364136223846793005u64 + 1442695040888963407u64;
+        epochs.push(((x >> 11) % 12622780800000u64) as i64 - 5364662400000);
+    }
+    epochs
 }
 
 #[cfg(test)]
 mod test {
-    use super::GCalendar;
+    use super::{GCalendar, InvalidDay, day_of_year, day_of_week, is_leap_year};
+    use super::{GCalendarJson, InvalidMonth, InvalidHour};
+    use super::{InvalidYear, InvalidMinute, InvalidSecond, InvalidMillisecond};
+    use super::{InvalidWeekday, InvalidYearDay};
+    use std::io;
+    use extra::json;
+    use extra::serialize::{Encodable, Decodable};
+    use super::year_size;
+    use super::MACEPOCHOFFSET;
+    use types::{February, Friday};
+    use extra::sort;
+    use super::random_epochs;
 
     #[test]
     fn new() {
@@ -397,9 +1052,517 @@ mod test {
     }
 
     #[test]
-    fn new_from_epoch() {
-        let gc = GCalendar::new_from_epoch(433166421023);
+    fn from_epoch_millis() {
+        let gc = GCalendar::from_epoch_millis(433166421023);
         assert_eq!(gc.get_day_of_week(), 5);
         assert_eq!(gc.get_day_of_year(), 265);
+
+        let gc = GCalendar::from_epoch_millis(-1);
+        assert_eq!(gc.all_components(), (1969, 12, 31, 23, 59, 59, 999, 3, 364));
+        assert_eq!(gc.mktime(), -1);
+        let gc = GCalendar::from_epoch_millis(-2208988800000);
+        assert_eq!(gc.all_components(), (1900, 1, 1, 0, 0, 0, 0, 1, 0));
+        assert_eq!(gc.mktime(), -2208988800);
+    }
+
+    #[test]
+    fn ydhms_diff() {
+        let gc = GCalendar::new_at_epoch();
+        assert_eq!(gc.ydhms_diff(1969, 0, 0, 0, 0, 1970, 0, 0, 0, 0), -31536000);
+        assert_eq!(gc.ydhms_diff(1970, 0, 0, 0, 0, 1969, 0, 0, 0, 0), 31536000);
+        /* 2008 is a leap year */
+        assert_eq!(gc.ydhms_diff(2008, 0, 0, 0, 0, 2009, 0, 0, 0, 0), -31622400);
+        assert_eq!(gc.ydhms_diff(2009, 0, 0, 0, 0, 2008, 0, 0, 0, 0), 31622400);
+
+        /* Same day, the first time being the earlier one */
+        assert_eq!(gc.ydhms_diff(2009, 43, 10, 0, 0, 2009, 43, 23, 31, 30), -48690);
+        assert_eq!(gc.ydhms_diff(2009, 43, 23, 31, 29, 2009, 43, 23, 31, 30), -1);
+        assert_eq!(gc.ydhms_diff(2009, 44, 0, 0, 0, 2009, 43, 23, 59, 59), 1);
+
+        let gc = GCalendar::new(0, 0, 12, 15, 6, 1950, 4, 165);
+        assert_eq!(gc.mktime(), -616852800);
+        let gc = GCalendar::new(59, 59, 23, 31, 12, 1969, 3, 364);
+        assert_eq!(gc.mktime(), -1);
+    }
+
+    #[test]
+    fn seconds_since_midnight() {
+        let gc = GCalendar::new(59, 59, 23, 13, 2, 2009, 5, 43);
+        assert_eq!(gc.seconds_since_midnight(), 86399);
+        assert_eq!(GCalendar::from_seconds_since_midnight(86399), (23, 59, 59));
+        assert_eq!(GCalendar::from_seconds_since_midnight(0), (0, 0, 0));
+
+        let gc = GCalendar::from_epoch_millis(1234567890543);
+        assert_eq!(gc.get_msec(), 543);
+        assert_eq!(gc.seconds_since_midnight(), 84690);
+        assert_eq!(gc.millis_since_midnight(), 84690543);
+    }
+
+    #[test]
+    fn test_day_of_year() {
+        assert_eq!(day_of_year(2009, 1, 1), Some(0));
+        assert_eq!(day_of_year(2009, 2, 13), Some(43));
+        assert_eq!(day_of_year(2008, 12, 31), Some(365));
+        assert_eq!(day_of_year(2009, 2, 29), None);
+        assert_eq!(day_of_year(2009, 13, 1), None);
+        assert_eq!(day_of_year(2009, 1, 0), None);
+    }
+
+    #[test]
+    fn from_epoch_millis_properties() {
+        for &e in random_epochs(1000).iter() {
+            let gc = GCalendar::from_epoch_millis(e);
+            assert_eq!(gc.mktime(), e.div_floor(&1000));
+            assert!(gc.msec < 1000 && gc.sec < 60 && gc.min < 60 && gc.hour < 24);
+            assert!(gc.month >= 1 && gc.month <= 12);
+            assert!(gc.wday < 7);
+            assert!(gc.yday < year_size(gc.year));
+            assert_eq!(day_of_year(gc.year, gc.month, gc.mday), Some(gc.yday));
+        }
+
+        /* 2024-12-31 and 2023-12-31 */
+        assert!(is_leap_year(GCalendar::from_epoch_millis(1735603200000).year));
+        assert_eq!(GCalendar::from_epoch_millis(1735603200000).yday, 365);
+        assert!(!is_leap_year(GCalendar::from_epoch_millis(1703980800000).year));
+        assert_eq!(GCalendar::from_epoch_millis(1703980800000).yday, 364);
+    }
+
+    #[test]
+    fn century() {
+        let century = |year: uint| GCalendar::new(0, 0, 0, 1, 1, year, 0, 0).century();
+        assert_eq!(century(1900), 19);
+        assert_eq!(century(1901), 20);
+        assert_eq!(century(2000), 20);
+        assert_eq!(century(2001), 21);
+        assert_eq!(century(2009), 21);
+    }
+
+    #[test]
+    fn test_day_of_week() {
+        assert_eq!(day_of_week(1970, 1, 1), 4);
+        assert_eq!(day_of_week(2009, 2, 13), 5);
+        assert_eq!(day_of_week(2000, 2, 29), 2);
+        assert_eq!(day_of_week(1600, 1, 1), 6);
+        for &e in random_epochs(100).iter() {
+            let gc = GCalendar::from_epoch_millis(e);
+            assert_eq!(day_of_week(gc.year, gc.month, gc.mday), gc.wday);
+        }
+    }
+
+    #[test]
+    fn from_ordinal_date() {
+        let gc = GCalendar::from_ordinal_date(2009, 1).unwrap();
+        assert_eq!((gc.year, gc.month, gc.mday, gc.wday, gc.yday), (2009, 1, 1, 4, 0));
+
+        let gc = GCalendar::from_ordinal_date(2008, 60).unwrap();
+        assert_eq!((gc.month, gc.mday, gc.wday), (2, 29, 5));
+        let gc = GCalendar::from_ordinal_date(2009, 60).unwrap();
+        assert_eq!((gc.month, gc.mday, gc.wday), (3, 1, 0));
+
+        let gc = GCalendar::from_ordinal_date(2008, 366).unwrap();
+        assert_eq!((gc.month, gc.mday, gc.wday, gc.yday), (12, 31, 3, 365));
+
+        assert_eq!(GCalendar::from_ordinal_date(2009, 366).unwrap_err(), InvalidDay);
+        assert_eq!(GCalendar::from_ordinal_date(2009, 0).unwrap_err(), InvalidDay);
+    }
+
+    #[test]
+    fn mac_absolute_time() {
+        let gc = GCalendar::from_mac_absolute_time(0.0);
+        assert_eq!((gc.year, gc.month, gc.mday, gc.hour, gc.min, gc.sec), (2001, 1, 1, 0, 0, 0));
+        assert_eq!(gc.to_mac_absolute_time(), 0.0);
+
+        /* 31 years later, 7 of them leap years */
+        let gc = GCalendar::from_mac_absolute_time(MACEPOCHOFFSET);
+        assert_eq!((gc.year, gc.month, gc.mday), (2032, 1, 2));
+
+        let gc = GCalendar::from_mac_absolute_time(256171890.5);
+        assert_eq!(gc.msec, 500);
+        assert_eq!(gc.to_mac_absolute_time(), 256171890.5);
+
+        let gc = GCalendar::from_mac_absolute_time(-MACEPOCHOFFSET);
+        assert_eq!((gc.year, gc.month, gc.mday), (1970, 1, 1));
+    }
+
+    #[test]
+    fn components() {
+        let gc = GCalendar::from_epoch_millis(1234567890543);
+        assert_eq!(gc.time_components(), (23, 31, 30, 543));
+        assert_eq!(gc.date_components(), (2009, 2, 13));
+        assert_eq!(gc.all_components(), (2009, 2, 13, 23, 31, 30, 543, 5, 43));
+
+        let (year, month, mday) = GCalendar::from_epoch_millis(0).date_components();
+        assert_eq!((year, month, mday), (1970, 1, 1));
+    }
+
+    #[test]
+    fn format_for_log() {
+        let gc = GCalendar::from_epoch_millis(1234567890030);
+        assert_eq!(gc.format_for_log(), ~"2009-02-13 23:31:30.030");
+        assert_eq!(GCalendar::from_epoch_millis(1234567890000).format_for_log(),
+                   ~"2009-02-13 23:31:30.000");
+        assert_eq!(GCalendar::from_epoch_millis(1234567890001).format_for_log(),
+                   ~"2009-02-13 23:31:30.001");
+        assert_eq!(GCalendar::from_epoch_millis(1234567890999).format_for_log(),
+                   ~"2009-02-13 23:31:30.999");
+
+        assert_eq!(gc.format_for_log_with_tz(0), ~"2009-02-13 23:31:30.030 +0000");
+        assert_eq!(gc.format_for_log_with_tz(19800), ~"2009-02-13 23:31:30.030 +0530");
+        assert_eq!(gc.format_for_log_with_tz(-18000), ~"2009-02-13 23:31:30.030 -0500");
+    }
+
+    #[test]
+    fn new_from_parts_normalized() {
+        let gc = GCalendar::new_from_parts_normalized(2009, 2, 13, 23, 31, 30);
+        assert_eq!(gc.all_components(), (2009, 2, 13, 23, 31, 30, 0, 5, 43));
+
+        let gc = GCalendar::new_from_parts_normalized(2008, 13, 1, 0, 0, 0);
+        assert_eq!(gc.date_components(), (2009, 1, 1));
+        let gc = GCalendar::new_from_parts_normalized(2009, 1, 31, 25, 0, 0);
+        assert_eq!(gc.date_components(), (2009, 2, 1));
+        assert_eq!(gc.time_components(), (1, 0, 0, 0));
+        let gc = GCalendar::new_from_parts_normalized(2009, 2, 29, 0, 0, 0);
+        assert_eq!(gc.date_components(), (2009, 3, 1));
+        let gc = GCalendar::new_from_parts_normalized(2008, 12, 31, 23, 59, 60);
+        assert_eq!(gc.all_components(), (2009, 1, 1, 0, 0, 0, 0, 4, 0));
+
+        let gc = GCalendar::new_from_parts_normalized(2009, 3, 0, 0, 0, 0);
+        assert_eq!((gc.date_components(), gc.yday), ((2009, 2, 28), 58));
+        let gc = GCalendar::new_from_parts_normalized(2009, 0, 1, 0, 0, -1);
+        assert_eq!(gc.date_components(), (2008, 11, 30));
+        assert_eq!(gc.time_components(), (23, 59, 59, 0));
+        let gc = GCalendar::new_from_parts_normalized(2009, 1, -365, 0, 0, 0);
+        assert_eq!(gc.date_components(), (2008, 1, 1));
+        let gc = GCalendar::new_from_parts_normalized(2009, 1, 400, 0, 0, 0);
+        assert_eq!(gc.date_components(), (2010, 2, 4));
+    }
+
+    #[test]
+    fn json() {
+        let gc = GCalendar::from_epoch_millis(1234567890543);
+        let fields = GCalendarJson::from_calendar(&gc);
+
+        let s = do io::with_str_writer |wr| {
+            let mut encoder = json::Encoder(wr);
+            fields.encode(&mut encoder);
+        };
+        assert_eq!(s, ~"{\"year\":2009,\"month\":2,\"day\":13," +
+                      "\"hour\":23,\"minute\":31,\"second\":30}");
+
+        let mut decoder = json::Decoder(json::from_str(s).unwrap());
+        let back: GCalendarJson = Decodable::decode(&mut decoder);
+        assert_eq!(back, fields);
+
+        let cal = back.to_calendar().unwrap();
+        assert_eq!(cal.all_components(), (2009, 2, 13, 23, 31, 30, 0, 5, 43));
+
+        let mut bad = fields.clone();
+        bad.month = 13;
+        assert_eq!(bad.to_calendar().unwrap_err(), InvalidMonth);
+        bad.month = 2;
+        bad.day = 29;
+        assert_eq!(bad.to_calendar().unwrap_err(), InvalidDay);
+        bad.day = 28;
+        bad.hour = 24;
+        assert_eq!(bad.to_calendar().unwrap_err(), InvalidHour);
+    }
+
+    #[test]
+    fn julian_year() {
+        let j2000 = GCalendar::from_epoch_millis(946728000000);
+        assert_eq!(j2000.to_julian_year(), 2000.0);
+
+        let gc = GCalendar::from_julian_year(2000.0);
+        assert_eq!(gc.all_components(), (2000, 1, 1, 12, 0, 0, 0, 6, 0));
+        let gc = GCalendar::from_julian_year(2010.0);
+        assert_eq!(gc.date_components(), (2010, 1, 1));
+        assert_eq!(gc.time_components(), (0, 0, 0, 0));
+        assert_eq!(gc.to_julian_year(), 2010.0);
+
+        let gc = GCalendar::from_epoch_millis(1234567890543);
+        assert!((GCalendar::from_julian_year(gc.to_julian_year()).mktime() as int -
+                 gc.mktime() as int).abs() <= 1);
+    }
+
+    #[test]
+    fn add_seconds_and_minutes() {
+        let mut gc = GCalendar::new_from_parts_normalized(2009, 2, 28, 23, 0, 0);
+        gc.msec = 543;
+        gc.add_seconds(3600);
+        assert_eq!(gc.all_components(), (2009, 3, 1, 0, 0, 0, 543, 0, 59));
+
+        gc.add_seconds(-1);
+        assert_eq!(gc.all_components(), (2009, 2, 28, 23, 59, 59, 543, 6, 58));
+
+        gc.add_minutes(60 * 24 * 365);
+        assert_eq!(gc.date_components(), (2010, 2, 28));
+        assert_eq!(gc.time_components(), (23, 59, 59, 543));
+
+        gc.add_minutes(-90);
+        assert_eq!(gc.time_components(), (22, 29, 59, 543));
+
+        for &e in random_epochs(100).iter() {
+            let mut gc = GCalendar::from_epoch_millis(e);
+            gc.add_seconds(86399);
+            assert_eq!(gc.all_components(),
+                       GCalendar::from_epoch_millis(e + 86399000).all_components());
+        }
+    }
+
+    #[test]
+    fn total_seconds_since_year_zero() {
+        let gc = GCalendar::new_at_epoch();
+        assert_eq!(gc.total_seconds_since_year_zero(), 62135596800);
+        assert_eq!(gc.mktime(), 0);
+
+        let gc = GCalendar::new_from_parts_normalized(1, 1, 1, 0, 0, 0);
+        assert_eq!(gc.total_seconds_since_year_zero(), 0);
+        let gc = GCalendar::new_from_parts_normalized(1, 1, 2, 0, 0, 1);
+        assert_eq!(gc.total_seconds_since_year_zero(), 86401);
+
+        let gc = GCalendar::from_epoch_millis(1234567890543);
+        assert_eq!(gc.total_seconds_since_year_zero(), 62135596800 + 1234567890);
+    }
+
+    #[test]
+    fn format_time() {
+        let gc = GCalendar::from_epoch_millis(1234567890030);
+        assert_eq!(gc.format_time_12h(), ~"11:31:30 PM");
+        assert_eq!(gc.format_time_24h(), ~"23:31:30");
+        assert_eq!(gc.format_time_24h_with_ms(), ~"23:31:30.030");
+
+        let midnight = GCalendar::from_epoch_millis(1234483200000);
+        assert_eq!(midnight.format_time_12h(), ~"12:00:00 AM");
+        assert_eq!(midnight.format_time_24h(), ~"00:00:00");
+        assert_eq!(midnight.format_time_24h_with_ms(), ~"00:00:00.000");
+
+        let noon = GCalendar::from_epoch_millis(1234526400000);
+        assert_eq!(noon.format_time_12h(), ~"12:00:00 PM");
+        assert_eq!(noon.format_time_24h(), ~"12:00:00");
+
+        let gc = GCalendar::from_epoch_millis(1234515600000);
+        assert_eq!(gc.format_time_12h(), ~"09:00:00 AM");
+        assert_eq!(gc.format_time_24h(), ~"09:00:00");
+    }
+
+    #[test]
+    fn chronological_order() {
+        let a = GCalendar::from_epoch_millis(1234567890000);
+        let b = GCalendar::new_from_parts_normalized(2009, 2, 14, 0, 0, 0);
+        assert!(a < b);
+        assert!(b > a);
+        assert_eq!(a.cmp(&b), Less);
+        assert_eq!(b.cmp(&b), Equal);
+
+        /* The same instant built differently, with a wrong day of week */
+        let c = GCalendar::new(30, 31, 23, 13, 2, 2009, 0, 0);
+        assert_eq!(a, c);
+        assert!(a.equals(&c));
+
+        let mut cals = ~[b, GCalendar::from_epoch_millis(-1), a,
+                         GCalendar::from_epoch_millis(1234567890001)];
+        sort::quick_sort(cals, |x, y| x <= y);
+        assert_eq!(cals[0].year, 1969);
+        assert_eq!(cals[1], a);
+        assert_eq!(cals[2].msec, 1);
+        assert_eq!(cals[3], b);
+    }
+
+    #[test]
+    fn new_at_epoch() {
+        let gc = GCalendar::new_at_epoch();
+        assert_eq!(gc.get_day_of_week(), 4);
+        assert_eq!(gc.all_components(), GCalendar::from_epoch_millis(0).all_components());
+        assert_eq!(gc.all_components(), (1970, 1, 1, 0, 0, 0, 0, 4, 0));
+    }
+
+    #[test]
+    fn month_and_weekday_enum() {
+        let gc = GCalendar::from_epoch_millis(1234567890543);
+        assert_eq!(gc.month_enum(), February);
+        assert_eq!(gc.weekday_enum(), Friday);
+        assert_eq!(gc.month_enum().to_str(), ~"February");
+        assert_eq!(gc.weekday_enum().to_str(), ~"Friday");
+    }
+
+    /* The year and day of the year of a day since epoch, a year at a time */
+    fn year_by_steps(days: i64) -> (uint, uint) {
+        let mut year = 1970;
+        let mut days = days;
+        while days < 0 {
+            year -= 1;
+            days += year_size(year) as i64;
+        }
+        let mut dayno = days as uint;
+        while dayno >= year_size(year) {
+            dayno -= year_size(year);
+            year += 1;
+        }
+        (year, dayno)
+    }
+
+    #[test]
+    fn from_epoch_millis_year() {
+        let check = |days: i64| {
+            let gc = GCalendar::from_epoch_millis(days * 86400000 + 43200000);
+            assert_eq!((gc.year, gc.yday), year_by_steps(days));
+        };
+
+        /* Year 0, epoch, and the first and last days around 2000 and 2100 */
+        for &days in [-719528i64, -719162, -1, 0, 10956, 10957, 11322, 11323, 47481,
+                      47482, 47846, 47847, 84005].iter() {
+            check(days);
+        }
+
+        /* Days up to 2200, and back to year 0 */
+        let mut seed = 1234567890u64;
+        for _ in range(0, 1000) {
+            seed = seed * 6364136223846793005 + 1442695040888963407;
+            check(((seed >> 33) % 84006) as i64);
+            check(-(((seed >> 13) % 719528) as i64) - 1);
+        }
+    }
+
+    #[test]
+    fn names() {
+        let gc = GCalendar::from_epoch_millis(1234567890543);
+        assert_eq!(gc.day_name(), "Friday");
+        assert_eq!(gc.day_name_short(), "Fri");
+        assert_eq!(gc.month_name(), "February");
+        assert_eq!(gc.month_name_short(), "Feb");
+
+        let gc = GCalendar::new_at_epoch();
+        assert_eq!(gc.day_name(), "Thursday");
+        assert_eq!(gc.month_name_short(), "Jan");
+        let gc = GCalendar::new_from_parts_normalized(2008, 12, 28, 0, 0, 0);
+        assert_eq!(gc.day_name_short(), "Sun");
+        assert_eq!(gc.month_name(), "December");
+    }
+
+    #[test]
+    fn validate() {
+        assert!(GCalendar::new(30, 31, 23, 13, 2, 2009, 5, 43).validate().is_ok());
+        assert!(GCalendar::new(60, 59, 23, 31, 12, 2008, 3, 365).validate().is_ok());
+        assert!(GCalendar::new(0, 0, 0, 29, 2, 2008, 5, 59).validate().is_ok());
+        assert!(GCalendar::new_at_epoch().validate().is_ok());
+
+        assert_eq!(GCalendar::new(0, 0, 0, 1, 13, 2009, 0, 0).validate(), Err(InvalidMonth));
+        assert_eq!(GCalendar::new(0, 0, 0, 1, 0, 2009, 0, 0).validate(), Err(InvalidMonth));
+        assert_eq!(GCalendar::new(0, 0, 0, 31, 2, 2009, 0, 0).validate(), Err(InvalidDay));
+        assert_eq!(GCalendar::new(0, 0, 0, 29, 2, 2009, 0, 0).validate(), Err(InvalidDay));
+        assert_eq!(GCalendar::new(0, 0, 0, 0, 1, 2009, 0, 0).validate(), Err(InvalidDay));
+        assert_eq!(GCalendar::new(0, 0, 24, 1, 1, 2009, 0, 0).validate(), Err(InvalidHour));
+        assert_eq!(GCalendar::new(0, 60, 0, 1, 1, 2009, 0, 0).validate(), Err(InvalidMinute));
+        assert_eq!(GCalendar::new(61, 0, 0, 1, 1, 2009, 0, 0).validate(), Err(InvalidSecond));
+        assert_eq!(GCalendar::new(0, 0, 0, 1, 1, 2009, 7, 0).validate(), Err(InvalidWeekday));
+        assert_eq!(GCalendar::new(0, 0, 0, 1, 1, 2009, 0, 365).validate(),
+                   Err(InvalidYearDay));
+
+        assert_eq!(GCalendar::new_checked(30, 31, 23, 13, 2, 2009, 5, 43).unwrap(),
+                   GCalendar::new(30, 31, 23, 13, 2, 2009, 5, 43));
+        assert_eq!(GCalendar::new_checked(0, 0, 0, 31, 4, 2009, 0, 0).unwrap_err(), InvalidDay);
+    }
+
+    #[test]
+    fn packed_bytes() {
+        let gc = GCalendar::from_epoch_millis(1234567890543);
+        let bytes = gc.to_packed_bytes();
+        assert_eq!(bytes, [0xd9, 0x07, 2, 13, 23, 31, 30, 5, 43, 0, 0x1f, 0x02,
+                           0, 0, 0, 0]);
+        assert_eq!(GCalendar::from_packed_bytes(&bytes).unwrap(), gc);
+
+        let gc = GCalendar::from_epoch_millis(-2208988800000);
+        assert_eq!(GCalendar::from_packed_bytes(&gc.to_packed_bytes()).unwrap(), gc);
+
+        let check = |i: uint, value: u8| {
+            let mut b = bytes;
+            b[i] = value;
+            GCalendar::from_packed_bytes(&b).unwrap_err()
+        };
+        assert_eq!(check(1, 0x80), InvalidYear);
+        assert_eq!(check(2, 13), InvalidMonth);
+        assert_eq!(check(3, 30), InvalidDay);
+        assert_eq!(check(4, 24), InvalidHour);
+        assert_eq!(check(5, 60), InvalidMinute);
+        assert_eq!(check(6, 61), InvalidSecond);
+        assert_eq!(check(7, 4), InvalidDay);
+        assert_eq!(check(8, 44), InvalidDay);
+        assert_eq!(check(11, 0x04), InvalidMillisecond);
+    }
+
+    #[test]
+    fn to_str() {
+        let gc = GCalendar::from_epoch_millis(1234567890543);
+        assert_eq!(gc.to_str(), ~"2009-02-13 23:31:30");
+        assert_eq!(GCalendar::new_at_epoch().to_str(), ~"1970-01-01 00:00:00");
+    }
+
+    #[test]
+    fn copy_and_clone() {
+        fn year_of(gc: GCalendar) -> uint { gc.year }
+        fn month_of(gc: GCalendar) -> uint { gc.month }
+
+        let gc = GCalendar::from_epoch_millis(1234567890543);
+        assert_eq!(year_of(gc), 2009);
+        assert_eq!(month_of(gc), 2);
+        assert_eq!(gc.clone(), gc);
+    }
+
+    #[test]
+    fn days_in_month() {
+        let normal = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+        let leap = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+        for month in range(1u32, 13) {
+            assert_eq!(GCalendar::days_in_month(2009, month), normal[month as uint - 1]);
+            assert_eq!(GCalendar::days_in_month(2008, month), leap[month as uint - 1]);
+        }
+        assert_eq!(GCalendar::days_in_month(1900, 2), 28);
+        assert_eq!(GCalendar::days_in_month(2000, 2), 29);
+
+        assert_eq!(GCalendar::from_epoch_millis(1234567890543).days_in_current_month(), 28);
+        assert_eq!(GCalendar::new_at_epoch().days_in_current_month(), 31);
+    }
+
+    #[test]
+    #[should_fail]
+    fn days_in_month_zero() {
+        GCalendar::days_in_month(2009, 0);
+    }
+
+    #[test]
+    fn iso_week() {
+        let weeks = [(1970, 1, 1, "1970-W01"), (1970, 1, 2, "1970-W01"),
+                      (1970, 1, 3, "1970-W01"), (1970, 1, 4, "1970-W01"),
+                      (1970, 12, 28, "1970-W53"), (1970, 12, 29, "1970-W53"),
+                      (1970, 12, 30, "1970-W53"), (1970, 12, 31, "1970-W53"),
+                      (2000, 1, 1, "1999-W52"), (2000, 1, 2, "1999-W52"),
+                      (2000, 1, 3, "2000-W01"), (2000, 1, 4, "2000-W01"),
+                      (2000, 12, 28, "2000-W52"), (2000, 12, 29, "2000-W52"),
+                      (2000, 12, 30, "2000-W52"), (2000, 12, 31, "2000-W52"),
+                      (2004, 1, 1, "2004-W01"), (2004, 1, 2, "2004-W01"),
+                      (2004, 1, 3, "2004-W01"), (2004, 1, 4, "2004-W01"),
+                      (2004, 12, 28, "2004-W53"), (2004, 12, 29, "2004-W53"),
+                      (2004, 12, 30, "2004-W53"), (2004, 12, 31, "2004-W53"),
+                      (2015, 1, 1, "2015-W01"), (2015, 1, 2, "2015-W01"),
+                      (2015, 1, 3, "2015-W01"), (2015, 1, 4, "2015-W01"),
+                      (2015, 12, 28, "2015-W53"), (2015, 12, 29, "2015-W53"),
+                      (2015, 12, 30, "2015-W53"), (2015, 12, 31, "2015-W53"),
+                      (2020, 1, 1, "2020-W01"), (2020, 1, 2, "2020-W01"),
+                      (2020, 1, 3, "2020-W01"), (2020, 1, 4, "2020-W01"),
+                      (2020, 12, 28, "2020-W53"), (2020, 12, 29, "2020-W53"),
+                      (2020, 12, 30, "2020-W53"), (2020, 12, 31, "2020-W53"),
+                      (2008, 12, 29, "2009-W01"), (2010, 1, 3, "2009-W53"),
+                      (2014, 12, 29, "2015-W01"), (2019, 12, 30, "2020-W01"),
+                      (2021, 1, 3, "2020-W53"), (1969, 12, 29, "1970-W01")];
+        for &(year, month, mday, week) in weeks.iter() {
+            let gc = GCalendar::new_from_parts_normalized(year, month, mday, 0, 0, 0);
+            assert_eq!(format!("{}-W{}", gc.iso_week('G'), gc.iso_week('V')), week.to_owned());
+        }
+
+        assert_eq!(GCalendar::new_at_epoch().get_date('V'), ~"01");
+        let gc = GCalendar::new_from_parts_normalized(2010, 1, 3, 0, 0, 0);
+        assert_eq!(gc.iso_year_week(), (2009, 53));
+        let gc = GCalendar::new_from_parts_normalized(2000, 1, 1, 0, 0, 0);
+        assert_eq!(gc.get_date('g'), ~"99");
     }
 }
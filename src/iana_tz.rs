@@ -0,0 +1,120 @@
+// Copyright 2013 Luis de Bethencourt <luis@debethencourt.com>
+// Copyright 2013 The Rust Project Developers
+// http://rust-lang.org
+
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * iana_tz module for the datetime library of the Rust programming language
+ *
+ * Standard time offsets of the most used IANA timezone IDs. Daylight saving
+ * time is not in the table.
+ *
+ * http://www.iana.org/time-zones
+ */
+
+/*
+ * Standard time offsets, in minutes east of UTC.
+ */
+static TIMEZONES: [(&'static str, int), ..66] = [
+    ("UTC", 0),
+    ("GMT", 0),
+    ("EST", -300),
+    ("CST", -360),
+    ("MST", -420),
+    ("PST", -480),
+    ("America/St_Johns", -210),
+    ("America/Halifax", -240),
+    ("America/New_York", -300),
+    ("America/Toronto", -300),
+    ("America/Detroit", -300),
+    ("America/Chicago", -360),
+    ("America/Mexico_City", -360),
+    ("America/Denver", -420),
+    ("America/Phoenix", -420),
+    ("America/Los_Angeles", -480),
+    ("America/Vancouver", -480),
+    ("America/Anchorage", -540),
+    ("Pacific/Honolulu", -600),
+    ("America/Bogota", -300),
+    ("America/Lima", -300),
+    ("America/Caracas", -240),
+    ("America/Santiago", -240),
+    ("America/Sao_Paulo", -180),
+    ("America/Argentina/Buenos_Aires", -180),
+    ("Atlantic/Reykjavik", 0),
+    ("Europe/London", 0),
+    ("Europe/Dublin", 0),
+    ("Europe/Lisbon", 0),
+    ("Europe/Paris", 60),
+    ("Europe/Berlin", 60),
+    ("Europe/Madrid", 60),
+    ("Europe/Rome", 60),
+    ("Europe/Amsterdam", 60),
+    ("Europe/Brussels", 60),
+    ("Europe/Vienna", 60),
+    ("Europe/Zurich", 60),
+    ("Europe/Stockholm", 60),
+    ("Europe/Oslo", 60),
+    ("Europe/Copenhagen", 60),
+    ("Europe/Prague", 60),
+    ("Europe/Warsaw", 60),
+    ("Europe/Athens", 120),
+    ("Europe/Helsinki", 120),
+    ("Europe/Kiev", 120),
+    ("Europe/Istanbul", 180),
+    ("Europe/Moscow", 180),
+    ("Africa/Lagos", 60),
+    ("Africa/Cairo", 120),
+    ("Africa/Johannesburg", 120),
+    ("Africa/Nairobi", 180),
+    ("Asia/Dubai", 240),
+    ("Asia/Tehran", 210),
+    ("Asia/Karachi", 300),
+    ("Asia/Kolkata", 330),
+    ("Asia/Kathmandu", 345),
+    ("Asia/Dhaka", 360),
+    ("Asia/Bangkok", 420),
+    ("Asia/Jakarta", 420),
+    ("Asia/Shanghai", 480),
+    ("Asia/Hong_Kong", 480),
+    ("Asia/Singapore", 480),
+    ("Asia/Seoul", 540),
+    ("Asia/Tokyo", 540),
+    ("Australia/Sydney", 600),
+    ("Pacific/Auckland", 720)
+];
+
+/**
+ * Returns the standard time offset, in minutes east of UTC, of the given
+ * timezone ID, or None if it is not in the table.
+ */
+pub fn iana_standard_offset(tz_id: &str) -> Option<int> {
+    for &(name, minutes) in TIMEZONES.iter() {
+        if name == tz_id {
+            return Some(minutes);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::iana_standard_offset;
+
+    #[test]
+    fn test_iana_standard_offset() {
+        assert_eq!(iana_standard_offset("UTC"), Some(0));
+        assert_eq!(iana_standard_offset("America/New_York"), Some(-300));
+        assert_eq!(iana_standard_offset("America/St_Johns"), Some(-210));
+        assert_eq!(iana_standard_offset("Europe/London"), Some(0));
+        assert_eq!(iana_standard_offset("Asia/Kolkata"), Some(330));
+        assert_eq!(iana_standard_offset("Asia/Kathmandu"), Some(345));
+        assert_eq!(iana_standard_offset("asia/kolkata"), None);
+        assert_eq!(iana_standard_offset("Mars/Olympus_Mons"), None);
+    }
+}
@@ -0,0 +1,573 @@
+// Copyright 2013 Luis de Bethencourt <luis@debethencourt.com>
+// Copyright 2013 The Rust Project Developers
+// http://rust-lang.org
+
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * parse module for the datetime library of the Rust programming language
+ *
+ * Reads dates and times from strings, using the same format specifiers
+ * as strftime.
+ */
+
+use std::str::CharRange;
+use gcalendar::{MONTH_NAMES, MONTH_ABBREVS, DAY_NAMES, DAY_ABBREVS};
+use iana_tz::iana_standard_offset;
+
+/**
+ * Errors reading a string. UnexpectedInput is given by parse_with_position
+ * instead of InvalidFormat: the byte position where the input stopped
+ * following the format, what the format expected there and the character
+ * found, None at the end of the input.
+ */
+#[deriving(Eq)]
+pub enum ParseError {
+    InvalidFormat,     /* Input does not follow the format     */
+    InvalidValue,      /* A field is out of its valid range    */
+    UnknownTimezone,   /* Timezone name is not in the table    */
+    AmbiguousFormat,   /* Input reads as different dates       */
+    UnknownSpecifier(char), /* Format has a specifier not read  */
+    UnexpectedInput(uint, ~str, Option<char>), /* See above */
+}
+
+impl ToStr for ParseError {
+    fn to_str(&self) -> ~str {
+        match *self {
+            InvalidFormat => ~"input does not follow the format",
+            InvalidValue => ~"a field is out of its valid range",
+            UnknownTimezone => ~"unknown timezone",
+            AmbiguousFormat => ~"input reads as different dates",
+            UnknownSpecifier(ch) => format!("unknown format specifier %{}", ch),
+            UnexpectedInput(pos, ref expected, Some(ch)) => {
+                format!("parse error at position {}: expected {}, got '{}'", pos, *expected, ch)
+            }
+            UnexpectedInput(pos, ref expected, None) => {
+                format!("parse error at position {}: expected {}, got end of input",
+                        pos, *expected)
+            }
+        }
+    }
+}
+
+pub struct Parsed {
+    /*
+     * Fields read from a string. Fields missing in the format keep their
+     * value at epoch.
+     */
+    year: uint,
+    month: uint,       /* Month         [1-12]  */
+    mday: uint,        /* Day           [1-31]  */
+    hour: uint,
+    min: uint,
+    sec: uint,
+    offset: int,       /* Seconds east of UTC   */
+    week_year: uint,   /* ISO 8601 week-based year */
+    week: uint,        /* ISO 8601 week     [1-53] */
+    weekday: uint,     /* ISO 8601 weekday  [1-7]  */
+    yday: uint,        /* Day of the year [1-366], 0 if not read */
+    timestamp: Option<i64>, /* Seconds since epoch, read by %s */
+}
+
+impl Parsed {
+    pub fn new() -> Parsed {
+        Parsed {
+            year: 1970,
+            month: 1,
+            mday: 1,
+            hour: 0,
+            min: 0,
+            sec: 0,
+            offset: 0,
+            week_year: 0,
+            week: 0,
+            weekday: 0,
+            yday: 0,
+            timestamp: None,
+        }
+    }
+}
+
+/**
+ * Reads the string according to the format string. The whole string has to
+ * be consumed.
+ */
+pub fn parse(s: &str, format: &str) -> Result<Parsed, ParseError> {
+    let mut p = Parsed::new();
+    let mut pos = 0u;
+
+    match parse_into(s, &mut pos, format, &mut p) {
+        Ok(()) => (),
+        Err(e) => return Err(e)
+    }
+
+    if pos < s.len() {
+        return Err(InvalidFormat);
+    }
+    Ok(p)
+}
+
+/**
+ * Like parse, but input not following the format gives UnexpectedInput,
+ * telling where reading stopped and what was expected there, instead of
+ * InvalidFormat.
+ */
+pub fn parse_with_position(s: &str, format: &str) -> Result<Parsed, ParseError> {
+    let mut p = Parsed::new();
+    let mut pos = 0u;
+    let mut fmt = format.iter();
+
+    loop {
+        let (res, expected) = match fmt.next() {
+            None => break,
+            Some('%') => match fmt.next() {
+                Some(ch) => (parse_spec(s, &mut pos, ch, &mut p), spec_expectation(ch)),
+                None => return Err(InvalidFormat)
+            },
+            Some(ch) => (expect_char(s, &mut pos, ch), format!("'{}'", ch))
+        };
+
+        match res {
+            Ok(()) => (),
+            Err(InvalidFormat) => return Err(unexpected_input(s, pos, expected)),
+            Err(e) => return Err(e)
+        }
+    }
+
+    if pos < s.len() {
+        return Err(unexpected_input(s, pos, ~"end of input"));
+    }
+    Ok(p)
+}
+
+/**
+ * Reads the string with the first of the format strings it follows.
+ */
+pub fn parse_first(s: &str, formats: &[&str]) -> Result<Parsed, ParseError> {
+    let mut err = InvalidFormat;
+
+    for format in formats.iter() {
+        match parse(s, *format) {
+            Ok(p) => return Ok(p),
+            Err(InvalidFormat) => (),
+            Err(e) => err = e
+        }
+    }
+    Err(err)
+}
+
+/**
+ * Returns the offset in seconds east of UTC of a timezone given either as a
+ * fixed offset ("Z", "+05:30", "-0500") or as a name ("Asia/Kolkata").
+ *
+ * Named timezones always get their standard time offset, daylight saving
+ * time is not applied.
+ */
+pub fn parse_timezone(tz: &str) -> Result<int, ParseError> {
+    // FIXME: Apply daylight saving time rules for named timezones
+    let mut pos = 0u;
+    match read_offset(tz, &mut pos) {
+        Ok(offset) if pos == tz.len() => return Ok(offset),
+        _ => ()
+    }
+
+    match iana_standard_offset(tz) {
+        Some(minutes) => Ok(minutes * 60),
+        None => Err(UnknownTimezone)
+    }
+}
+
+/**
+ * Reads a time of day with no date, "HH:MM", "HH:MM:SS", "HH:MM:SS.mmm" or
+ * any of them followed by " AM" or " PM", and returns (hour, min, sec, ms).
+ */
+pub fn parse_time(s: &str) -> Result<(uint, uint, uint, uint), ParseError> {
+    let mut pos = 0u;
+    let (mut hour, mut min, mut sec, mut ms) = (0u, 0u, 0u, 0u);
+
+    match read_field(s, &mut pos, 2, &mut hour) {
+        Ok(()) => (),
+        Err(e) => return Err(e)
+    }
+    match expect_char(s, &mut pos, ':') {
+        Ok(()) => (),
+        Err(e) => return Err(e)
+    }
+    match read_field(s, &mut pos, 2, &mut min) {
+        Ok(()) => (),
+        Err(e) => return Err(e)
+    }
+
+    if skip_char(s, &mut pos, ':') {
+        match read_field(s, &mut pos, 2, &mut sec) {
+            Ok(()) => (),
+            Err(e) => return Err(e)
+        }
+        if skip_char(s, &mut pos, '.') {
+            let start = pos;
+            match read_field(s, &mut pos, 3, &mut ms) {
+                Ok(()) => (),
+                Err(e) => return Err(e)
+            }
+            for _ in range(pos - start, 3) {
+                ms *= 10;
+            }
+        }
+    }
+
+    if skip_char(s, &mut pos, ' ') {
+        let pm = match read_name(s, &mut pos, &["AM", "PM"]) {
+            Ok(i) => i == 1,
+            Err(e) => return Err(e)
+        };
+        if hour < 1 || hour > 12 {
+            return Err(InvalidValue);
+        }
+        hour = hour % 12 + if pm { 12 } else { 0 };
+    }
+
+    if pos < s.len() {
+        return Err(InvalidFormat);
+    }
+    if hour > 23 || min > 59 || sec > 60 {
+        return Err(InvalidValue);
+    }
+    Ok((hour, min, sec, ms))
+}
+
+fn parse_into(s: &str, pos: &mut uint, format: &str, p: &mut Parsed)
+              -> Result<(), ParseError> {
+    let mut fmt = format.iter();
+
+    loop {
+        let res = match fmt.next() {
+            None => break,
+            Some('%') => match fmt.next() {
+                Some(ch) => parse_spec(s, pos, ch, p),
+                None => Err(InvalidFormat)
+            },
+            Some(ch) => expect_char(s, pos, ch)
+        };
+
+        match res {
+            Ok(()) => (),
+            Err(e) => return Err(e)
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_spec(s: &str, pos: &mut uint, ch: char, p: &mut Parsed)
+              -> Result<(), ParseError> {
+    match ch {
+        'Y' => read_field(s, pos, 4, &mut p.year),
+        'm' => read_field(s, pos, 2, &mut p.month),
+        'd' => read_field(s, pos, 2, &mut p.mday),
+        'e' => {
+            skip_char(s, pos, ' ');
+            read_field(s, pos, 2, &mut p.mday)
+        }
+        'A' => match read_name(s, pos, DAY_NAMES) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e)
+        },
+        'a' => match read_name(s, pos, DAY_ABBREVS) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e)
+        },
+        'B' => match read_name(s, pos, MONTH_NAMES) {
+            Ok(i) => {
+                p.month = i + 1;
+                Ok(())
+            }
+            Err(e) => Err(e)
+        },
+        'b' | 'h' => match read_name(s, pos, MONTH_ABBREVS) {
+            Ok(i) => {
+                p.month = i + 1;
+                Ok(())
+            }
+            Err(e) => Err(e)
+        },
+        'G' => read_field(s, pos, 4, &mut p.week_year),
+        'H' => read_field(s, pos, 2, &mut p.hour),
+        'j' => read_field(s, pos, 3, &mut p.yday),
+        'M' => read_field(s, pos, 2, &mut p.min),
+        'S' => read_field(s, pos, 2, &mut p.sec),
+        's' => {
+            let negative = skip_char(s, pos, '-');
+            match read_number(s, pos, 18) {
+                Ok(n) => {
+                    p.timestamp = Some(if negative { -(n as i64) } else { n as i64 });
+                    Ok(())
+                }
+                Err(e) => Err(e)
+            }
+        }
+        'T' => parse_into(s, pos, "%H:%M:%S", p),
+        'u' => read_field(s, pos, 1, &mut p.weekday),
+        'V' => read_field(s, pos, 2, &mut p.week),
+        'z' => match read_offset(s, pos) {
+            Ok(offset) => {
+                p.offset = offset;
+                Ok(())
+            }
+            Err(e) => Err(e)
+        },
+        'Z' => {
+            let start = *pos;
+            while *pos < s.len() && is_tz_name_char(s[*pos]) {
+                *pos += 1;
+            }
+            match parse_timezone(s.slice(start, *pos)) {
+                Ok(offset) => {
+                    p.offset = offset;
+                    Ok(())
+                }
+                Err(e) => Err(e)
+            }
+        }
+        '%' => expect_char(s, pos, '%'),
+        _   => Err(UnknownSpecifier(ch))
+    }
+}
+
+/*
+ * What a specifier expects to read, for UnexpectedInput.
+ */
+fn spec_expectation(ch: char) -> ~str {
+    match ch {
+        'A' | 'a' => ~"weekday name",
+        'B' | 'b' | 'h' => ~"month name",
+        'T' => ~"time",
+        'z' => ~"UTC offset",
+        'Z' => ~"timezone",
+        '%' => ~"'%'",
+        _ => ~"digit"
+    }
+}
+
+fn unexpected_input(s: &str, pos: uint, expected: ~str) -> ParseError {
+    let got = if pos < s.len() { Some(s.char_at(pos)) } else { None };
+    UnexpectedInput(pos, expected, got)
+}
+
+/*
+ * Reads between one and max_digits decimal digits.
+ */
+fn read_number(s: &str, pos: &mut uint, max_digits: uint)
+               -> Result<uint, ParseError> {
+    let mut n = 0u;
+    let mut digits = 0u;
+
+    while digits < max_digits && *pos < s.len() && is_digit(s[*pos]) {
+        n = n * 10 + (s[*pos] - '0' as u8) as uint;
+        digits += 1;
+        *pos += 1;
+    }
+
+    if digits == 0 { Err(InvalidFormat) } else { Ok(n) }
+}
+
+/*
+ * Reads one of the names, ignoring case, and returns its index.
+ */
+fn read_name(s: &str, pos: &mut uint, names: &[&'static str])
+             -> Result<uint, ParseError> {
+    let start = *pos;
+
+    for (i, &name) in names.iter().enumerate() {
+        let end = start + name.len();
+        if end <= s.len() &&
+           range(0, name.len()).all(|j| to_lower(s[start + j]) == to_lower(name[j])) {
+            *pos = end;
+            return Ok(i);
+        }
+    }
+    Err(InvalidFormat)
+}
+
+fn read_field(s: &str, pos: &mut uint, max_digits: uint, field: &mut uint)
+              -> Result<(), ParseError> {
+    match read_number(s, pos, max_digits) {
+        Ok(n) => {
+            *field = n;
+            Ok(())
+        }
+        Err(e) => Err(e)
+    }
+}
+
+/*
+ * Reads a UTC offset, "Z", "+HH", "+HHMM" or "+HH:MM", returning it in
+ * seconds east of UTC.
+ */
+fn read_offset(s: &str, pos: &mut uint) -> Result<int, ParseError> {
+    if skip_char(s, pos, 'Z') {
+        return Ok(0);
+    }
+
+    let sign = if skip_char(s, pos, '+') {
+        1
+    } else if skip_char(s, pos, '-') {
+        -1
+    } else {
+        return Err(InvalidFormat);
+    };
+
+    let hours = match read_number(s, pos, 2) {
+        Ok(n) => n,
+        Err(e) => return Err(e)
+    };
+    let minutes = if skip_char(s, pos, ':') ||
+                     (*pos < s.len() && is_digit(s[*pos])) {
+        match read_number(s, pos, 2) {
+            Ok(n) => n,
+            Err(e) => return Err(e)
+        }
+    } else {
+        0
+    };
+
+    if hours > 23 || minutes > 59 {
+        return Err(InvalidValue);
+    }
+    Ok(sign * ((hours * 3600 + minutes * 60) as int))
+}
+
+fn expect_char(s: &str, pos: &mut uint, ch: char) -> Result<(), ParseError> {
+    if *pos < s.len() {
+        let CharRange {ch: c, next} = s.char_range_at(*pos);
+        if c == ch {
+            *pos = next;
+            return Ok(());
+        }
+    }
+    Err(InvalidFormat)
+}
+
+fn skip_char(s: &str, pos: &mut uint, ch: char) -> bool {
+    expect_char(s, pos, ch).is_ok()
+}
+
+fn is_digit(b: u8) -> bool {
+    b >= '0' as u8 && b <= '9' as u8
+}
+
+fn is_tz_name_char(b: u8) -> bool {
+    (b >= 'A' as u8 && b <= 'Z' as u8) || (b >= 'a' as u8 && b <= 'z' as u8) ||
+        b == '/' as u8 || b == '_' as u8
+}
+
+fn to_lower(b: u8) -> u8 {
+    if b >= 'A' as u8 && b <= 'Z' as u8 { b + 32 } else { b }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, parse_first, parse_timezone, parse_time, InvalidFormat,
+                InvalidValue, UnknownTimezone, UnknownSpecifier};
+    use super::{parse_with_position, UnexpectedInput};
+
+    #[test]
+    fn test_parse() {
+        let p = parse("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!((p.year, p.month, p.mday), (2009, 2, 13));
+        assert_eq!((p.hour, p.min, p.sec), (23, 31, 30));
+
+        let p = parse("Feb  3 09:05:00 +0530", "Feb %e %T %z").unwrap();
+        assert_eq!((p.mday, p.hour, p.min, p.sec), (3, 9, 5, 0));
+        assert_eq!(p.offset, 19800);
+
+        assert_eq!(parse("2009-02-13", "%Y/%m/%d").unwrap_err(), InvalidFormat);
+        assert_eq!(parse("2009-02-13 ", "%Y-%m-%d").unwrap_err(), InvalidFormat);
+        assert_eq!(parse("2009-02-", "%Y-%m-%d").unwrap_err(), InvalidFormat);
+        assert_eq!(parse("2009-02-13", "%Y-%m-%Q").unwrap_err(), UnknownSpecifier('Q'));
+
+        let p = parse("2009-044 1234567890", "%Y-%j %s").unwrap();
+        assert_eq!((p.year, p.yday), (2009, 44));
+        assert_eq!(p.timestamp, Some(1234567890));
+        assert_eq!(parse("-86400", "%s").unwrap().timestamp, Some(-86400));
+        assert_eq!(parse("", "%s").unwrap_err(), InvalidFormat);
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(parse_timezone("Z"), Ok(0));
+        assert_eq!(parse_timezone("UTC"), Ok(0));
+        assert_eq!(parse_timezone("+05:30"), Ok(19800));
+        assert_eq!(parse_timezone("-0500"), Ok(-18000));
+        assert_eq!(parse_timezone("+05"), Ok(18000));
+        assert_eq!(parse_timezone("Asia/Kolkata"), Ok(19800));
+        assert_eq!(parse_timezone("America/New_York"), Ok(-18000));
+        assert_eq!(parse_timezone("+25:00"), Err(InvalidValue));
+        assert_eq!(parse_timezone("Mars/Olympus_Mons"), Err(UnknownTimezone));
+    }
+
+    #[test]
+    fn test_parse_names() {
+        let p = parse("Fri, 13 Feb 2009 18:31:30 EST", "%a, %d %b %Y %T %Z").unwrap();
+        assert_eq!((p.year, p.month, p.mday), (2009, 2, 13));
+        assert_eq!(p.offset, -18000);
+
+        let p = parse("friday 13 FEBRUARY 2009", "%A %d %B %Y").unwrap();
+        assert_eq!(p.month, 2);
+        assert_eq!(parse("Fry 13", "%a %d").unwrap_err(), InvalidFormat);
+
+        let formats = &["%Y-%m-%d", "%d %b %Y"];
+        assert_eq!(parse_first("13 Feb 2009", formats).unwrap().month, 2);
+        assert_eq!(parse_first("2009-02-13", formats).unwrap().mday, 13);
+        assert_eq!(parse_first("13/02/2009", formats).unwrap_err(), InvalidFormat);
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("14:30"), Ok((14, 30, 0, 0)));
+        assert_eq!(parse_time("14:30:05"), Ok((14, 30, 5, 0)));
+        assert_eq!(parse_time("14:30:05.123"), Ok((14, 30, 5, 123)));
+        assert_eq!(parse_time("14:30:05.5"), Ok((14, 30, 5, 500)));
+        assert_eq!(parse_time("2:30 PM"), Ok((14, 30, 0, 0)));
+        assert_eq!(parse_time("12:05 am"), Ok((0, 5, 0, 0)));
+        assert_eq!(parse_time("12:05 PM"), Ok((12, 5, 0, 0)));
+        assert_eq!(parse_time("11:59:59 PM"), Ok((23, 59, 59, 0)));
+
+        assert_eq!(parse_time("25:00"), Err(InvalidValue));
+        assert_eq!(parse_time("14:60"), Err(InvalidValue));
+        assert_eq!(parse_time("13:00 PM"), Err(InvalidValue));
+        assert_eq!(parse_time("0:30 AM"), Err(InvalidValue));
+        assert_eq!(parse_time("14"), Err(InvalidFormat));
+        assert_eq!(parse_time("14:30 XM"), Err(InvalidFormat));
+        assert_eq!(parse_time("14:30:05.1234"), Err(InvalidFormat));
+    }
+
+    #[test]
+    fn test_parse_with_position() {
+        let p = parse_with_position("2009-02-13", "%Y-%m-%d").unwrap();
+        assert_eq!((p.year, p.month, p.mday), (2009, 2, 13));
+
+        let e = parse_with_position("2009-XY-13", "%Y-%m-%d").unwrap_err();
+        assert_eq!(e, UnexpectedInput(5, ~"digit", Some('X')));
+        assert_eq!(e.to_str(), ~"parse error at position 5: expected digit, got 'X'");
+
+        assert_eq!(parse_with_position("2009/02/13", "%Y-%m-%d").unwrap_err(),
+                   UnexpectedInput(4, ~"'-'", Some('/')));
+        assert_eq!(parse_with_position("2009-02-", "%Y-%m-%d").unwrap_err().to_str(),
+                   ~"parse error at position 8: expected digit, got end of input");
+        assert_eq!(parse_with_position("2009-02-13Z", "%Y-%m-%d").unwrap_err(),
+                   UnexpectedInput(10, ~"end of input", Some('Z')));
+        assert_eq!(parse_with_position("13 Fev 2009", "%d %b %Y").unwrap_err(),
+                   UnexpectedInput(3, ~"month name", Some('F')));
+        assert_eq!(parse_with_position("12:30:xx", "%T").unwrap_err(),
+                   UnexpectedInput(6, ~"time", Some('x')));
+
+        assert_eq!(parse_with_position("2009-13-13", "%Y-%m-%q").unwrap_err(),
+                   UnknownSpecifier('q'));
+        assert_eq!(UnknownSpecifier('q').to_str(), ~"unknown format specifier %q");
+        assert_eq!(InvalidValue.to_str(), ~"a field is out of its valid range");
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright 2013 Luis de Bethencourt <luis@debethencourt.com>
+// Copyright 2013 The Rust Project Developers
+// http://rust-lang.org
+
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * period module for the datetime library of the Rust programming language
+ *
+ * A period is the span of time between two dates, including its start and
+ * excluding its end.
+ */
+
+use extra::sort;
+use date::Date;
+
+pub struct Period {
+    /**
+    * First instant inside the period.
+    */
+    priv start: Date,
+    /**
+    * First instant after the period.
+    */
+    priv end: Date,
+}
+
+impl Period {
+    /**
+    * Allocates a Period object going from start (included) to end (excluded).
+    */
+    pub fn new(start: Date, end: Date) -> Period {
+        Period {
+            start: start,
+            end: end
+        }
+    }
+
+    pub fn get_start(&self) -> Date {
+        self.start
+    }
+
+    pub fn get_end(&self) -> Date {
+        self.end
+    }
+
+    /**
+     * Returns true if the date falls inside this period.
+     */
+    pub fn contains(&self, date: &Date) -> bool {
+        let t = date.get_time();
+        self.start.get_time() <= t && t < self.end.get_time()
+    }
+}
+
+/**
+ * Merges overlapping and adjacent periods into the smallest set of
+ * non-overlapping periods, sorted by start date.
+ */
+pub fn merge_periods(periods: ~[Period]) -> ~[Period] {
+    let mut periods = periods;
+    sort::quick_sort(periods, |a, b| a.start.get_time() <= b.start.get_time());
+
+    let mut merged: ~[Period] = ~[];
+    for p in periods.iter() {
+        let last = merged.len();
+        if last > 0 && p.start.get_time() <= merged[last - 1].end.get_time() {
+            if p.end.get_time() > merged[last - 1].end.get_time() {
+                merged[last - 1].end = p.end;
+            }
+        } else {
+            merged.push(*p);
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Period, merge_periods};
+    use date::Date;
+
+    fn period(start: i64, end: i64) -> Period {
+        Period::new(Date::from_timestamp_millis(start),
+                    Date::from_timestamp_millis(end))
+    }
+
+    #[test]
+    fn contains() {
+        let p = period(1000, 2000);
+        assert!(p.contains(&Date::from_timestamp_millis(1000)));
+        assert!(p.contains(&Date::from_timestamp_millis(1999)));
+        assert!(!p.contains(&Date::from_timestamp_millis(2000)));
+        assert!(!p.contains(&Date::from_timestamp_millis(999)));
+    }
+
+    #[test]
+    fn test_merge_periods() {
+        let merged = merge_periods(~[period(5000, 6000), period(1000, 2000),
+                                     period(1500, 3000), period(3000, 4000)]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].get_start().get_time(), 1000);
+        assert_eq!(merged[0].get_end().get_time(), 4000);
+        assert_eq!(merged[1].get_start().get_time(), 5000);
+        assert_eq!(merged[1].get_end().get_time(), 6000);
+
+        assert_eq!(merge_periods(~[]).len(), 0);
+    }
+}
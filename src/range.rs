@@ -0,0 +1,120 @@
+// Copyright 2013 Luis de Bethencourt <luis@debethencourt.com>
+// Copyright 2013 The Rust Project Developers
+// http://rust-lang.org
+
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * range module for the datetime library of the Rust programming language
+ *
+ * A date range steps through the dates from a start, included, to an end,
+ * excluded, a fixed duration at a time.
+ */
+
+use date::Date;
+use duration::Duration;
+
+pub struct DateRange {
+    /**
+    * Next date to give.
+    */
+    priv current: Date,
+    /**
+    * First date not to give.
+    */
+    priv end: Date,
+    /**
+    * Duration between two dates, negative to go backwards.
+    */
+    priv step: Duration,
+}
+
+impl DateRange {
+    /**
+    * Allocates a DateRange object going from start (included) to end
+    * (excluded) by step. A negative step goes backwards, from a start after
+    * the end. Fails if step is zero.
+    */
+    pub fn new(start: Date, end: Date, step: Duration) -> DateRange {
+        if step.as_millis() == 0 {
+            fail!("date range step is zero");
+        }
+        DateRange {
+            current: start,
+            end: end,
+            step: step
+        }
+    }
+
+    /**
+    * Returns the same range with another step.
+    */
+    pub fn step_by(&self, step: Duration) -> DateRange {
+        DateRange::new(self.current, self.end, step)
+    }
+}
+
+impl Iterator<Date> for DateRange {
+    fn next(&mut self) -> Option<Date> {
+        let (t, end) = (self.current.get_time(), self.end.get_time());
+        let done = if self.step.as_millis() > 0 { t >= end } else { t <= end };
+        if done {
+            return None;
+        }
+
+        let date = self.current;
+        self.current = match self.current.checked_add(&self.step) {
+            Some(next) => next,
+            /* Before year 0, so past any end */
+            None => self.end
+        };
+        Some(date)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DateRange;
+    use date::Date;
+    use duration::Duration;
+
+    #[test]
+    fn date_range() {
+        let start = Date::from_ymd(2009, 2, 13).unwrap();
+        let end = Date::from_ymd(2009, 2, 16).unwrap();
+
+        let days: ~[~str] = DateRange::new(start, end, Duration::from_days(1))
+            .map(|d| d.strftime("%F")).collect();
+        assert_eq!(days, ~[~"2009-02-13", ~"2009-02-14", ~"2009-02-15"]);
+
+        let days: ~[~str] = start.until(end).map(|d| d.strftime("%F")).collect();
+        assert_eq!(days, ~[~"2009-02-13", ~"2009-02-14", ~"2009-02-15"]);
+
+        let hours = start.until(end).step_by(Duration::from_hours(12));
+        let hours: ~[~str] = hours.map(|d| d.strftime("%d %H")).collect();
+        assert_eq!(hours, ~[~"13 00", ~"13 12", ~"14 00", ~"14 12", ~"15 00", ~"15 12"]);
+
+        let back: ~[~str] = DateRange::new(end, start, Duration::from_days(-1))
+            .map(|d| d.strftime("%F")).collect();
+        assert_eq!(back, ~[~"2009-02-16", ~"2009-02-15", ~"2009-02-14"]);
+
+        assert_eq!(start.until(start).count(), 0);
+        assert_eq!(end.until(start).count(), 0);
+
+        let zero = Date::from_ymd(0, 1, 3).unwrap();
+        let days: ~[~str] = DateRange::new(zero, Date::from_ymd(0, 1, 1).unwrap(),
+                                           Duration::from_days(-3))
+            .map(|d| d.strftime("%m-%d")).collect();
+        assert_eq!(days, ~[~"01-03"]);
+    }
+
+    #[test]
+    #[should_fail]
+    fn zero_step() {
+        DateRange::new(Date::unix_epoch(), Date::now(), Duration::from_days(0));
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2013 Luis de Bethencourt <luis@debethencourt.com>
+// Copyright 2013 The Rust Project Developers
+// http://rust-lang.org
+
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * timezone module for the datetime library of the Rust programming language
+ *
+ * Timezones a Date can be seen from, see Date::with_timezone.
+ */
+
+/**
+ * A timezone, known by its offset from UTC and its name.
+ */
+pub trait TimeZone {
+    /**
+    * Returns the offset of the timezone, in seconds east of UTC.
+    */
+    fn offset_seconds(&self) -> i32;
+
+    /**
+    * Returns the name of the timezone, as written by %Z.
+    */
+    fn name(&self) -> ~str;
+}
+
+/**
+ * A timezone at a fixed offset from UTC. For offsets west of UTC both fields
+ * are negative: -03:30 is FixedOffset::new(-3, -30).
+ */
+#[deriving(Eq, Clone)]
+pub struct FixedOffset {
+    hours: i32,
+    minutes: i32,
+}
+
+impl FixedOffset {
+    /**
+    * Allocates a FixedOffset object of the given hours and minutes east of
+    * UTC.
+    */
+    pub fn new(hours: i32, minutes: i32) -> FixedOffset {
+        FixedOffset { hours: hours, minutes: minutes }
+    }
+
+    /**
+    * Allocates a FixedOffset object at UTC.
+    */
+    pub fn utc() -> FixedOffset {
+        FixedOffset::new(0, 0)
+    }
+}
+
+impl TimeZone for FixedOffset {
+    fn offset_seconds(&self) -> i32 {
+        self.hours * 3600 + self.minutes * 60
+    }
+
+    /**
+    * "UTC" at no offset, otherwise the offset as in "+05:30".
+    */
+    fn name(&self) -> ~str {
+        let minutes = self.offset_seconds() / 60;
+        if minutes == 0 {
+            return ~"UTC";
+        }
+        let sign = if minutes < 0 { '-' } else { '+' };
+        format!("{}{:02d}:{:02d}", sign, minutes.abs() / 60, minutes.abs() % 60)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TimeZone, FixedOffset};
+
+    #[test]
+    fn fixed_offset() {
+        let ist = FixedOffset::new(5, 30);
+        assert_eq!(ist.offset_seconds(), 19800);
+        assert_eq!(ist.name(), ~"+05:30");
+
+        let nst = FixedOffset::new(-3, -30);
+        assert_eq!(nst.offset_seconds(), -12600);
+        assert_eq!(nst.name(), ~"-03:30");
+
+        assert_eq!(FixedOffset::utc().offset_seconds(), 0);
+        assert_eq!(FixedOffset::utc().name(), ~"UTC");
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2013 Luis de Bethencourt <luis@debethencourt.com>
+// Copyright 2013 The Rust Project Developers
+// http://rust-lang.org
+
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * types module for the datetime library of the Rust programming language
+ *
+ * Small value types shared by the other modules.
+ */
+
+use gcalendar::{MONTH_NAMES, DAY_NAMES};
+
+/**
+ * Month of the year, numbered from January (1) to December (12).
+ */
+#[deriving(Eq, Clone)]
+pub enum Month {
+    January = 1,
+    February = 2,
+    March = 3,
+    April = 4,
+    May = 5,
+    June = 6,
+    July = 7,
+    August = 8,
+    September = 9,
+    October = 10,
+    November = 11,
+    December = 12,
+}
+
+static MONTHS: [Month, ..12] = [January, February, March, April, May, June, July,
+                                August, September, October, November, December];
+
+impl Month {
+    /**
+    * Returns the month of the given number [1-12].
+    */
+    pub fn from_number(n: u32) -> Option<Month> {
+        if n < 1 || n > 12 { None } else { Some(MONTHS[n as uint - 1]) }
+    }
+
+    /**
+    * Returns the number [1-12] of the month.
+    */
+    pub fn number(&self) -> u32 {
+        *self as u32
+    }
+}
+
+impl ToStr for Month {
+    fn to_str(&self) -> ~str {
+        MONTH_NAMES[*self as uint - 1].to_owned()
+    }
+}
+
+/**
+ * Day of the week, numbered the ISO 8601 way from Monday (1) to Sunday (7).
+ */
+#[deriving(Eq, Clone)]
+pub enum Weekday {
+    Monday = 1,
+    Tuesday = 2,
+    Wednesday = 3,
+    Thursday = 4,
+    Friday = 5,
+    Saturday = 6,
+    Sunday = 7,
+}
+
+static WEEKDAYS: [Weekday, ..7] = [Monday, Tuesday, Wednesday, Thursday, Friday,
+                                   Saturday, Sunday];
+
+impl Weekday {
+    /**
+    * Returns the weekday of the given number [1-7], Monday being 1.
+    */
+    pub fn from_number(n: u32) -> Option<Weekday> {
+        if n < 1 || n > 7 { None } else { Some(WEEKDAYS[n as uint - 1]) }
+    }
+
+    /**
+    * Returns the weekday of the given number [0-6], Sunday being 0, as in
+    * GCalendar.
+    */
+    pub fn from_number_from_sunday(n: u32) -> Option<Weekday> {
+        if n > 6 { None } else { Some(WEEKDAYS[(n as uint + 6) % 7]) }
+    }
+
+    /**
+    * Returns the number [1-7] of the weekday, Monday being 1.
+    */
+    pub fn number_from_monday(&self) -> u32 {
+        *self as u32
+    }
+
+    /**
+    * Returns the number [0-6] of the weekday, Sunday being 0, as in
+    * GCalendar.
+    */
+    pub fn number_from_sunday(&self) -> u32 {
+        *self as u32 % 7
+    }
+}
+
+impl ToStr for Weekday {
+    fn to_str(&self) -> ~str {
+        DAY_NAMES[self.number_from_sunday() as uint].to_owned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Weekday, Monday, Saturday, Sunday};
+    use super::{Month, January, February, December};
+
+    #[test]
+    fn numbers() {
+        assert_eq!(Monday.number_from_monday(), 1);
+        assert_eq!(Sunday.number_from_monday(), 7);
+        assert_eq!(Monday.number_from_sunday(), 1);
+        assert_eq!(Sunday.number_from_sunday(), 0);
+
+        assert_eq!(Weekday::from_number(6), Some(Saturday));
+        assert_eq!(Weekday::from_number(0), None);
+        assert_eq!(Weekday::from_number_from_sunday(0), Some(Sunday));
+        assert_eq!(Weekday::from_number_from_sunday(6), Some(Saturday));
+        assert_eq!(Weekday::from_number_from_sunday(7), None);
+    }
+
+    #[test]
+    fn months() {
+        assert_eq!(Month::from_number(1), Some(January));
+        assert_eq!(Month::from_number(12), Some(December));
+        assert_eq!(Month::from_number(0), None);
+        assert_eq!(Month::from_number(13), None);
+        assert_eq!(February.number(), 2);
+        assert_eq!(February.to_str(), ~"February");
+    }
+
+    #[test]
+    fn weekday_names() {
+        assert_eq!(Weekday::from_number(1), Some(Monday));
+        assert_eq!(Weekday::from_number(8), None);
+        assert_eq!(Monday.to_str(), ~"Monday");
+        assert_eq!(Sunday.to_str(), ~"Sunday");
+    }
+}